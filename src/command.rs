@@ -1,14 +1,37 @@
 use structopt::StructOpt;
 
+mod advise;
+mod batch;
+mod completions;
+mod doctor;
+mod ensure;
+mod export;
+mod find;
 mod info;
+mod manpage;
 mod print;
+mod resolve;
 #[cfg(feature = "http")]
 mod serve;
+mod socket;
+mod whatis;
 
 #[derive(Debug, StructOpt)]
+#[structopt(about)]
 pub enum Command {
   Print(print::PrintCommand),
   Info(info::InfoCommand),
+  Find(find::FindCommand),
+  Ensure(ensure::EnsureCommand),
+  Export(export::ExportCommand),
+  Batch(batch::BatchCommand),
+  Resolve(resolve::ResolveCommand),
+  Doctor(doctor::DoctorCommand),
+  Socket(socket::SocketCommand),
+  Whatis(whatis::WhatisCommand),
+  Completions(completions::CompletionsCommand),
+  Advise(advise::AdviseCommand),
+  Manpage(manpage::ManpageCommand),
   #[cfg(feature = "http")]
   Serve(serve::ServeCommand),
 }
@@ -19,6 +42,17 @@ impl Command {
     match self {
       Self::Print(command) => command.execute()?,
       Self::Info(command) => command.execute()?,
+      Self::Find(command) => command.execute()?,
+      Self::Ensure(command) => command.execute()?,
+      Self::Export(command) => command.execute()?,
+      Self::Batch(command) => command.execute()?,
+      Self::Resolve(command) => command.execute()?,
+      Self::Doctor(command) => command.execute()?,
+      Self::Socket(command) => command.execute()?,
+      Self::Whatis(command) => command.execute()?,
+      Self::Completions(command) => command.execute()?,
+      Self::Advise(command) => command.execute()?,
+      Self::Manpage(command) => command.execute()?,
       #[cfg(feature = "http")]
       Self::Serve(command) => command.execute().await?,
     }
@@ -31,6 +65,17 @@ impl Command {
     match self {
       Self::Print(command) => command.execute()?,
       Self::Info(command) => command.execute()?,
+      Self::Find(command) => command.execute()?,
+      Self::Ensure(command) => command.execute()?,
+      Self::Export(command) => command.execute()?,
+      Self::Batch(command) => command.execute()?,
+      Self::Resolve(command) => command.execute()?,
+      Self::Doctor(command) => command.execute()?,
+      Self::Socket(command) => command.execute()?,
+      Self::Whatis(command) => command.execute()?,
+      Self::Completions(command) => command.execute()?,
+      Self::Advise(command) => command.execute()?,
+      Self::Manpage(command) => command.execute()?,
     }
 
     Ok(())
@@ -0,0 +1,40 @@
+use crate::output::FormatOptions;
+use crate::platform::{
+  Characteristics, OptionalProjectOptions, Resolver, ResolverOptions, StructuredAdvice,
+};
+use crate::Result;
+use camino::Utf8PathBuf;
+use structopt::StructOpt;
+
+#[derive(Debug, StructOpt)]
+#[structopt(about = "recommend a directory for data with the given characteristics")]
+pub struct AdviseCommand {
+  #[structopt(flatten)]
+  characteristics: Characteristics,
+  #[structopt(flatten)]
+  project: OptionalProjectOptions,
+  #[structopt(flatten)]
+  resolver: ResolverOptions,
+  #[structopt(flatten)]
+  format: FormatOptions,
+}
+
+impl AdviseCommand {
+  pub fn execute(self) -> Result<()> {
+    let Self {
+      characteristics,
+      project,
+      resolver,
+      format: FormatOptions { format },
+    } = self;
+
+    let resolver = Resolver::try_from(&resolver)?;
+    let project = project.project();
+    let advice = characteristics.advise(&resolver, project.as_ref())?;
+    let path = Utf8PathBuf::try_from(advice.resolved.clone())?.into_string();
+    let advice = StructuredAdvice::new(advice, path, project.is_some());
+    println!("{}", advice.render(&format)?);
+
+    Ok(())
+  }
+}
@@ -0,0 +1,279 @@
+use crate::output::{Format, FormatOptions};
+use crate::platform::PlatformPathKind as Kind;
+use crate::platform::{Resolver, ResolverOptions, StructuredPath};
+use crate::{
+  Base, Error, Integration, Project, ProjectOptions, Result, Scope, System, Tooling,
+  ToolingOptions, User,
+};
+use camino::Utf8PathBuf;
+use std::io::{self, BufRead};
+use std::path::PathBuf;
+use structopt::StructOpt;
+
+#[derive(Debug, StructOpt)]
+#[structopt(
+  about = "print paths for requests read from STDIN, one per line",
+  long_about = "print paths for requests read from STDIN, one per line
+
+Each request names a kind and a path, as with `print`, followed by any options as key=value pairs:
+
+    base cache
+    project config app=NiftyGate org=\"SUSE Software Solutions\" qualifier=com.suse
+    system data app=NiftyGate
+    tooling completion shell=fish
+
+Blank lines and lines starting with # are ignored. Each request produces one line of output, and failed requests are reported inline."
+)]
+pub struct BatchCommand {
+  #[structopt(flatten)]
+  resolver: ResolverOptions,
+  #[structopt(flatten)]
+  format: FormatOptions,
+}
+
+impl BatchCommand {
+  pub fn execute(self) -> Result<()> {
+    let Self {
+      resolver,
+      format: FormatOptions { format },
+    } = self;
+
+    let resolver = Resolver::try_from(&resolver)?;
+    let separator = resolver.platform.list_separator().to_string();
+
+    // Lines are read as bytes, so that a line that is not valid UTF-8 is
+    // reported like any other failed request.
+    for line in io::stdin().lock().split(b'\n') {
+      let path = match answer(&resolver, line?) {
+        Some(path) => path,
+        None => continue,
+      };
+
+      let output = match (&path, &format) {
+        (StructuredPath::Paths(paths), Format::Text) => paths.paths.join(&separator),
+        #[cfg(feature = "json")]
+        (_, Format::JsonPretty) => path.render(&Format::Json)?,
+        _ => path.render(&format)?,
+      };
+
+      println!("{output}");
+    }
+
+    Ok(())
+  }
+}
+
+/// The answer to one line of input, or None for blank lines and comments.
+fn answer(resolver: &Resolver, line: Vec<u8>) -> Option<StructuredPath> {
+  let path = match String::from_utf8(line) {
+    Ok(line) if line.trim().is_empty() || line.trim().starts_with('#') => return None,
+    Ok(line) => match request(resolver, line.trim()) {
+      Ok(path) => path.sandboxed(resolver),
+      Err(error) => StructuredPath::from(error),
+    },
+    Err(_) => StructuredPath::from(Error::InvalidRequest(
+      "request is not valid UTF-8".to_string(),
+    )),
+  };
+
+  Some(path)
+}
+
+fn request(resolver: &Resolver, line: &str) -> Result<StructuredPath> {
+  let invalid = |reason: &str| Error::InvalidRequest(format!("{reason}: {line}"));
+  let words = split(line).ok_or_else(|| invalid("unterminated quote"))?;
+  let (kind, path, options) = match words.as_slice() {
+    [kind, path, options @ ..] => (kind, path, options),
+    _ => return Err(invalid("expected a kind and a path")),
+  };
+
+  let mut qualifier = None;
+  let mut organization = None;
+  let mut application = None;
+  let mut scope = Scope::default();
+  let mut tooling = ToolingOptions::default();
+  for option in options {
+    match option.split_once('=') {
+      Some(("app" | "application", value)) => application = Some(value.to_string()),
+      Some(("org" | "organization", value)) => organization = Some(value.to_string()),
+      Some(("qualifier", value)) => qualifier = Some(value.to_string()),
+      Some(("scope", value)) => {
+        scope = value
+          .parse()
+          .map_err(|_| invalid(&format!("unrecognized scope {value}")))?
+      }
+      Some(("shell", value)) => {
+        tooling.shell = value
+          .parse()
+          .map_err(|_| invalid(&format!("unrecognized shell {value}")))?
+      }
+      Some(("section", value)) => tooling.section = value.to_string(),
+      _ => return Err(invalid(&format!("unrecognized option {option}"))),
+    }
+  }
+  let project = application.map(|application| ProjectOptions {
+    qualifier,
+    organization,
+    application,
+    scope,
+  });
+
+  let kind = kind
+    .parse::<Kind>()
+    .map_err(|_| invalid(&format!("unrecognized kind {kind}")))?;
+  let name = |_| Error::InvalidPathName(path.to_string());
+  let utf8 = |path: PathBuf| Ok(Utf8PathBuf::try_from(path)?.into_string());
+
+  let path = match kind {
+    Kind::Base => utf8(path.parse::<Base>().map_err(name)?.resolve(resolver)?)?.into(),
+    Kind::User => utf8(path.parse::<User>().map_err(name)?.resolve(resolver)?)?.into(),
+    Kind::Tooling => {
+      let path = path.parse::<Tooling>().map_err(name)?;
+      utf8(path.resolve(resolver, &tooling)?)?.into()
+    }
+    Kind::Integration => {
+      let path = path.parse::<Integration>().map_err(name)?;
+      utf8(path.resolve(resolver)?)?.into()
+    }
+    Kind::Project => {
+      let options = project.ok_or_else(|| invalid("project requests require app=<name>"))?;
+      let path = path.parse::<Project>().map_err(name)?;
+      utf8(path.resolve(resolver, &options)?)?.into()
+    }
+    Kind::System => {
+      let path = path.parse::<System>().map_err(name)?;
+      let paths = path.resolve(resolver, project.as_ref())?;
+      let paths = paths.into_iter().map(utf8).collect::<Result<Vec<_>>>()?;
+      paths.into()
+    }
+  };
+
+  Ok(path)
+}
+
+/// Splits a line into words at whitespace, keeping double-quoted text together.
+fn split(line: &str) -> Option<Vec<String>> {
+  let mut words = Vec::new();
+  let mut word: Option<String> = None;
+  let mut quoted = false;
+  let mut chars = line.chars();
+
+  while let Some(c) = chars.next() {
+    match c {
+      '"' => {
+        quoted = !quoted;
+        word.get_or_insert_with(String::new);
+      }
+      '\\' if quoted => word.get_or_insert_with(String::new).push(chars.next()?),
+      c if c.is_whitespace() && !quoted => words.extend(word.take()),
+      c => word.get_or_insert_with(String::new).push(c),
+    }
+  }
+
+  match quoted {
+    true => None,
+    false => {
+      words.extend(word);
+      Some(words)
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::platform::{Environment, Platform};
+
+  fn resolver() -> Resolver {
+    let env = Environment::new("/home/alice").with_var("XDG_DATA_DIRS", "/usr/share");
+    Resolver::new(Platform::Linux, env)
+  }
+
+  fn answer(line: &str) -> Option<String> {
+    super::answer(&resolver(), line.as_bytes().to_vec())
+      .map(|path| path.render(&Format::Text).unwrap())
+  }
+
+  fn words(words: &[&str]) -> Option<Vec<String>> {
+    Some(words.iter().map(ToString::to_string).collect())
+  }
+
+  #[test]
+  fn words_are_split_at_whitespace() {
+    assert_eq!(split("base  cache\t"), words(&["base", "cache"]));
+    assert_eq!(split(""), words(&[]));
+  }
+
+  #[test]
+  fn quoted_words_are_kept_together() {
+    assert_eq!(
+      split(r#"project config org="SUSE Software Solutions" app=Nifty"#),
+      words(&[
+        "project",
+        "config",
+        "org=SUSE Software Solutions",
+        "app=Nifty"
+      ])
+    );
+    assert_eq!(split(r#"a "" b"#), words(&["a", "", "b"]));
+    assert_eq!(
+      split(r#"app="say \"hi\" \\o/""#),
+      words(&[r#"app=say "hi" \o/"#])
+    );
+  }
+
+  #[test]
+  fn unterminated_quotes_are_rejected() {
+    assert_eq!(split(r#"project config app="Nifty"#), None);
+    assert_eq!(split(r#"app="Nifty\"#), None);
+  }
+
+  #[test]
+  fn blank_lines_and_comments_have_no_answer() {
+    assert_eq!(answer(""), None);
+    assert_eq!(answer("   \t"), None);
+    assert_eq!(answer("# base cache"), None);
+    assert_eq!(answer("  # indented"), None);
+  }
+
+  #[test]
+  fn requests_are_resolved() {
+    assert_eq!(answer("base cache"), Some("/home/alice/.cache".to_string()));
+    assert_eq!(
+      answer(r#"project config app="Nifty Gate" org=SUSE"#),
+      Some("/home/alice/.config/niftygate".to_string())
+    );
+    assert_eq!(answer("system data"), Some("/usr/share".to_string()));
+  }
+
+  #[test]
+  fn bad_lines_are_answered_with_an_error() {
+    let error = |line: &str| request(&resolver(), line).unwrap_err().to_string();
+    assert_eq!(
+      error("base"),
+      "invalid request: expected a kind and a path: base"
+    );
+    assert_eq!(
+      error("galaxy cache"),
+      "invalid request: unrecognized kind galaxy: galaxy cache"
+    );
+    assert_eq!(
+      error("base cache color=blue"),
+      "invalid request: unrecognized option color=blue: base cache color=blue"
+    );
+    assert_eq!(
+      error("project config"),
+      "invalid request: project requests require app=<name>: project config"
+    );
+    assert_eq!(
+      error(r#"base "cache"#),
+      r#"invalid request: unterminated quote: base "cache"#
+    );
+  }
+
+  #[test]
+  fn lines_that_are_not_utf8_are_answered_with_an_error() {
+    let path = super::answer(&resolver(), b"base \xff".to_vec()).unwrap();
+    assert!(matches!(path, StructuredPath::Error(_)));
+  }
+}
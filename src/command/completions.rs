@@ -0,0 +1,25 @@
+use crate::Result;
+use std::io;
+use structopt::clap::Shell;
+use structopt::StructOpt;
+
+#[derive(Debug, StructOpt)]
+#[structopt(about = "print a tab-completion script for a shell to STDOUT")]
+pub struct CompletionsCommand {
+  #[structopt(possible_values = &Shell::variants(), case_insensitive = true)]
+  shell: Shell,
+}
+
+impl CompletionsCommand {
+  pub fn execute(self) -> Result<()> {
+    let Self { shell } = self;
+
+    // The completions are generated from the same definition that parses the
+    // arguments, so they only offer what this binary was compiled with.
+    let mut app = super::Command::clap();
+    let name = app.get_name().to_string();
+    app.gen_completions_to(name, shell, &mut io::stdout());
+
+    Ok(())
+  }
+}
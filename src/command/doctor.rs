@@ -0,0 +1,31 @@
+use crate::output::FormatOptions;
+use crate::platform::{Diagnosis, OptionalProjectOptions, Resolver, ResolverOptions};
+use crate::Result;
+use structopt::StructOpt;
+
+#[derive(Debug, StructOpt)]
+#[structopt(about = "check that every directory is usable, for inclusion in support requests")]
+pub struct DoctorCommand {
+  #[structopt(flatten)]
+  project: OptionalProjectOptions,
+  #[structopt(flatten)]
+  resolver: ResolverOptions,
+  #[structopt(flatten)]
+  format: FormatOptions,
+}
+
+impl DoctorCommand {
+  pub fn execute(self) -> Result<()> {
+    let Self {
+      project,
+      resolver,
+      format: FormatOptions { format },
+    } = self;
+
+    let resolver = Resolver::try_from(&resolver)?;
+    let diagnosis = Diagnosis::diagnose(&resolver, project.project().as_ref());
+    println!("{}", diagnosis.render(&format)?);
+
+    Ok(())
+  }
+}
@@ -0,0 +1,51 @@
+use crate::output::FormatOptions;
+use crate::platform::{
+  Base, Project, ProjectOptions, Resolver, ResolverOptions, StructuredPath, User,
+};
+use crate::Result;
+use camino::Utf8PathBuf;
+use structopt::StructOpt;
+
+#[derive(Debug, StructOpt)]
+#[structopt(about = "create a directory if it is missing, and print its path to STDOUT")]
+pub struct EnsureCommand {
+  #[structopt(flatten)]
+  resolver: ResolverOptions,
+  #[structopt(flatten)]
+  format: FormatOptions,
+  #[structopt(subcommand)]
+  path: EnsurePath,
+}
+
+impl EnsureCommand {
+  pub fn execute(self) -> Result<()> {
+    let Self {
+      resolver,
+      format: FormatOptions { format },
+      path,
+    } = self;
+
+    let resolver = Resolver::try_from(&resolver)?;
+    let path = match path {
+      EnsurePath::User(path) => path.ensure(&resolver),
+      EnsurePath::Base(path) => path.ensure(&resolver),
+      EnsurePath::Project { path, options } => path.ensure(&resolver, &options),
+    }?;
+
+    let path = StructuredPath::from(Utf8PathBuf::try_from(path)?.into_string());
+    println!("{}", path.sandboxed(&resolver).render(&format)?);
+    Ok(())
+  }
+}
+
+#[derive(Debug, StructOpt)]
+enum EnsurePath {
+  User(User),
+  Base(Base),
+  Project {
+    #[structopt(subcommand)]
+    path: Project,
+    #[structopt(flatten)]
+    options: ProjectOptions,
+  },
+}
@@ -0,0 +1,263 @@
+use crate::platform::PlatformPathKind as Kind;
+use crate::platform::{OptionalProjectOptions, Resolver, ResolverOptions};
+use crate::{Base, Integration, Project, Result, Tooling, ToolingOptions, User};
+use anyhow::anyhow;
+use camino::Utf8PathBuf;
+use std::path::PathBuf;
+use structopt::StructOpt;
+use strum::{Display, EnumString, EnumVariantNames, IntoEnumIterator, VariantNames};
+
+#[derive(Debug, StructOpt)]
+#[structopt(about = "print shell variable assignments for every path of a kind")]
+pub struct ExportCommand {
+  #[structopt(possible_values = &["base", "user", "project", "integration", "tooling"])]
+  kind: Kind,
+  #[structopt(long, value_name = "shell", default_value, possible_values = Shell::VARIANTS)]
+  shell: Shell,
+  #[structopt(
+    long,
+    value_name = "string",
+    help = "prefix for variable names (defaults to the kind, or the application for project paths)"
+  )]
+  prefix: Option<String>,
+  #[structopt(long, value_name = "mode", default_value, possible_values = Unsupported::VARIANTS)]
+  unsupported: Unsupported,
+  #[structopt(flatten)]
+  project: OptionalProjectOptions,
+  #[structopt(flatten)]
+  resolver: ResolverOptions,
+}
+
+impl ExportCommand {
+  pub fn execute(self) -> anyhow::Result<()> {
+    let Self {
+      kind,
+      shell,
+      prefix,
+      unsupported,
+      project,
+      resolver,
+    } = self;
+
+    let resolver = Resolver::try_from(&resolver)?;
+    let project = project.project();
+    let prefix = match (prefix, &kind, &project) {
+      (Some(prefix), _, _) => prefix,
+      (None, Kind::Project, Some(options)) => options.application.clone(),
+      (None, kind, _) => kind.to_string(),
+    };
+
+    let paths: Vec<(String, Result<PathBuf>)> = match kind {
+      Kind::Base => Base::iter()
+        .map(|path| (path.to_string(), path.resolve(&resolver)))
+        .collect(),
+      Kind::User => User::iter()
+        .map(|path| (path.to_string(), path.resolve(&resolver)))
+        .collect(),
+      Kind::Integration => Integration::iter()
+        .map(|path| (path.to_string(), path.resolve(&resolver)))
+        .collect(),
+      Kind::Tooling => {
+        let options = ToolingOptions::default();
+        Tooling::iter()
+          .map(|path| (path.to_string(), path.resolve(&resolver, &options)))
+          .collect()
+      }
+      Kind::Project => {
+        let options = project
+          .as_ref()
+          .ok_or_else(|| anyhow!("exporting project paths requires --project-application"))?;
+        // The path fragment is relative, so it is not a directory by itself.
+        Project::iter()
+          .filter(|path| *path != Project::PathFragment)
+          .map(|path| (path.to_string(), path.resolve(&resolver, options)))
+          .collect()
+      }
+      Kind::System => return Err(anyhow!("system paths are lists, and cannot be exported")),
+    };
+
+    for (name, path) in paths {
+      let name = variable_name(&[&prefix, &name, "dir"]);
+      let path = path.and_then(|path| Ok(Utf8PathBuf::try_from(path)?));
+      match (path, &unsupported) {
+        (Ok(path), _) => println!("{}", shell.assign(&name, path.as_str())),
+        (Err(error), Unsupported::Comment) => {
+          println!("{}", shell.comment(&format!("{name}: {error}")))
+        }
+        (Err(_), Unsupported::Skip) => (),
+      }
+    }
+
+    Ok(())
+  }
+}
+
+/// Joins the parts into an upper-case variable name, replacing anything that
+/// is not valid in a variable name with underscores.
+fn variable_name(parts: &[&str]) -> String {
+  let name: String = parts
+    .join("_")
+    .chars()
+    .map(|c| match c.is_ascii_alphanumeric() {
+      true => c.to_ascii_uppercase(),
+      false => '_',
+    })
+    .collect();
+
+  match name.starts_with(|c: char| c.is_ascii_digit()) {
+    true => format!("_{name}"),
+    false => name,
+  }
+}
+
+#[derive(Debug, Default, Display, EnumString, EnumVariantNames)]
+#[strum(serialize_all = "kebab-case")]
+enum Shell {
+  #[default]
+  Posix,
+  Bash,
+  Fish,
+  Powershell,
+  Nushell,
+  Cmd,
+}
+
+impl Shell {
+  fn assign(&self, name: &str, value: &str) -> String {
+    match self {
+      Self::Posix | Self::Bash => {
+        let value = value.replace('\'', r#"'\''"#);
+        format!("export {name}='{value}'")
+      }
+      Self::Fish => {
+        let value = value.replace('\\', r"\\").replace('\'', r"\'");
+        format!("set -gx {name} '{value}'")
+      }
+      Self::Powershell => {
+        let value = value.replace('\'', "''");
+        format!("$env:{name} = '{value}'")
+      }
+      Self::Nushell => {
+        let value = value.replace('\\', r"\\").replace('"', r#"\""#);
+        format!("$env.{name} = \"{value}\"")
+      }
+      Self::Cmd => {
+        let value = value.replace('%', "%%");
+        format!("set \"{name}={value}\"")
+      }
+    }
+  }
+
+  fn comment(&self, text: &str) -> String {
+    match self {
+      Self::Cmd => format!("REM {text}"),
+      _ => format!("# {text}"),
+    }
+  }
+}
+
+#[derive(Debug, Default, Display, EnumString, EnumVariantNames)]
+#[strum(serialize_all = "kebab-case")]
+enum Unsupported {
+  #[default]
+  Comment,
+  Skip,
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  const VALUES: [&str; 4] = [
+    "/home/alice/My Files",
+    r#"/home/o'brien/"quoted"\dir"#,
+    "/home/$USER/cache",
+    "C:\\Users\\%USERNAME%\\AppData",
+  ];
+
+  fn assignments(shell: Shell) -> Vec<String> {
+    VALUES
+      .iter()
+      .map(|value| shell.assign("NIFTY_CACHE_DIR", value))
+      .collect()
+  }
+
+  #[test]
+  fn posix_shells_single_quote_values() {
+    for shell in [Shell::Posix, Shell::Bash] {
+      assert_eq!(
+        assignments(shell),
+        [
+          r#"export NIFTY_CACHE_DIR='/home/alice/My Files'"#,
+          r#"export NIFTY_CACHE_DIR='/home/o'\''brien/"quoted"\dir'"#,
+          r#"export NIFTY_CACHE_DIR='/home/$USER/cache'"#,
+          r#"export NIFTY_CACHE_DIR='C:\Users\%USERNAME%\AppData'"#,
+        ]
+      );
+    }
+  }
+
+  #[test]
+  fn fish_escapes_backslashes_and_quotes() {
+    assert_eq!(
+      assignments(Shell::Fish),
+      [
+        r#"set -gx NIFTY_CACHE_DIR '/home/alice/My Files'"#,
+        r#"set -gx NIFTY_CACHE_DIR '/home/o\'brien/"quoted"\\dir'"#,
+        r#"set -gx NIFTY_CACHE_DIR '/home/$USER/cache'"#,
+        r#"set -gx NIFTY_CACHE_DIR 'C:\\Users\\%USERNAME%\\AppData'"#,
+      ]
+    );
+  }
+
+  #[test]
+  fn powershell_doubles_single_quotes() {
+    assert_eq!(
+      assignments(Shell::Powershell),
+      [
+        r#"$env:NIFTY_CACHE_DIR = '/home/alice/My Files'"#,
+        r#"$env:NIFTY_CACHE_DIR = '/home/o''brien/"quoted"\dir'"#,
+        r#"$env:NIFTY_CACHE_DIR = '/home/$USER/cache'"#,
+        r#"$env:NIFTY_CACHE_DIR = 'C:\Users\%USERNAME%\AppData'"#,
+      ]
+    );
+  }
+
+  #[test]
+  fn nushell_escapes_backslashes_and_double_quotes() {
+    assert_eq!(
+      assignments(Shell::Nushell),
+      [
+        r#"$env.NIFTY_CACHE_DIR = "/home/alice/My Files""#,
+        r#"$env.NIFTY_CACHE_DIR = "/home/o'brien/\"quoted\"\\dir""#,
+        r#"$env.NIFTY_CACHE_DIR = "/home/$USER/cache""#,
+        r#"$env.NIFTY_CACHE_DIR = "C:\\Users\\%USERNAME%\\AppData""#,
+      ]
+    );
+  }
+
+  #[test]
+  fn cmd_doubles_percent_signs() {
+    assert_eq!(
+      assignments(Shell::Cmd),
+      [
+        r#"set "NIFTY_CACHE_DIR=/home/alice/My Files""#,
+        r#"set "NIFTY_CACHE_DIR=/home/o'brien/"quoted"\dir""#,
+        r#"set "NIFTY_CACHE_DIR=/home/$USER/cache""#,
+        r#"set "NIFTY_CACHE_DIR=C:\Users\%%USERNAME%%\AppData""#,
+      ]
+    );
+  }
+
+  #[test]
+  fn variable_names_are_upper_case_identifiers() {
+    assert_eq!(
+      variable_name(&["Nifty Gate", "cache", "dir"]),
+      "NIFTY_GATE_CACHE_DIR"
+    );
+    assert_eq!(
+      variable_name(&["1password", "config", "dir"]),
+      "_1PASSWORD_CONFIG_DIR"
+    );
+  }
+}
@@ -0,0 +1,84 @@
+use crate::output::FormatOptions;
+use crate::platform::PlatformPathKind as Kind;
+use crate::platform::{OptionalProjectOptions, Resolver, ResolverOptions, StructuredPath};
+use crate::{Base, Error, Integration, Project, Result, System, Tooling, ToolingOptions, User};
+use camino::Utf8PathBuf;
+use std::path::PathBuf;
+use structopt::StructOpt;
+use strum::VariantNames;
+
+#[derive(Debug, StructOpt)]
+#[structopt(about = "find an existing file in the user and system directories")]
+pub struct FindCommand {
+  #[structopt(possible_values = Kind::VARIANTS)]
+  kind: Kind,
+  #[structopt(help = "the directory to search, as named by `print`")]
+  path: String,
+  #[structopt(help = "the relative path of the file to find")]
+  name: PathBuf,
+  #[structopt(long, help = "print every match, rather than only the first")]
+  all: bool,
+  #[structopt(flatten)]
+  project: OptionalProjectOptions,
+  #[structopt(flatten)]
+  resolver: ResolverOptions,
+  #[structopt(flatten)]
+  format: FormatOptions,
+}
+
+impl FindCommand {
+  pub fn execute(self) -> Result<()> {
+    let Self {
+      kind,
+      path,
+      name,
+      all,
+      project,
+      resolver,
+      format: FormatOptions { format },
+    } = self;
+
+    let resolver = Resolver::try_from(&resolver)?;
+    let project = project.project();
+    let invalid = |_| Error::InvalidPathName(path.clone());
+    let search_paths = match kind {
+      Kind::Base => path
+        .parse::<Base>()
+        .map_err(invalid)?
+        .search_paths(&resolver)?,
+      Kind::User => vec![path.parse::<User>().map_err(invalid)?.resolve(&resolver)?],
+      Kind::Tooling => {
+        let path = path.parse::<Tooling>().map_err(invalid)?;
+        vec![path.resolve(&resolver, &ToolingOptions::default())?]
+      }
+      Kind::Integration => {
+        let path = path.parse::<Integration>().map_err(invalid)?;
+        vec![path.resolve(&resolver)?]
+      }
+      Kind::Project => {
+        let options = project
+          .as_ref()
+          .ok_or(Error::NotDefinedByPlatformStandard)?;
+        let path = path.parse::<Project>().map_err(invalid)?;
+        path.search_paths(&resolver, options)?
+      }
+      Kind::System => {
+        let path = path.parse::<System>().map_err(invalid)?;
+        path.resolve(&resolver, project.as_ref())?
+      }
+    };
+
+    let found = crate::find(&search_paths, name)?
+      .into_iter()
+      .map(|path| Ok(Utf8PathBuf::try_from(path)?.into_string()))
+      .collect::<Result<Vec<String>>>()?;
+
+    let found = match all {
+      true => StructuredPath::from(found),
+      false => StructuredPath::from(found[0].clone()),
+    };
+
+    println!("{}", found.sandboxed(&resolver).render(&format)?);
+    Ok(())
+  }
+}
@@ -1,10 +1,11 @@
 use crate::output::FormatOptions;
 use crate::platform::PlatformPathKind as Kind;
-use crate::platform::{Info, InfoFilter, Platform, Status};
+use crate::platform::{Attribute, Info, InfoFilter, Platform, Status};
 use structopt::StructOpt;
 use strum::{IntoEnumIterator, VariantNames};
 
 #[derive(Debug, StructOpt)]
+#[structopt(about = "print which paths each platform supports, and their attributes")]
 pub struct InfoCommand {
   #[structopt(flatten)]
   filter: FilterOptions,
@@ -20,6 +21,14 @@ struct FilterOptions {
   kind: Option<Kind>,
   #[structopt(short = "s", long = "status", possible_values = Status::VARIANTS)]
   status: Option<Status>,
+  #[structopt(
+    short = "a",
+    long = "attribute",
+    possible_values = Attribute::VARIANTS,
+    number_of_values = 1,
+    help = "only list paths with this attribute (may be repeated)"
+  )]
+  attributes: Vec<Attribute>,
 }
 
 impl InfoCommand {
@@ -59,6 +68,7 @@ impl From<FilterOptions> for InfoFilter {
       platforms,
       paths,
       statuses,
+      attributes: options.attributes,
     }
   }
 }
@@ -0,0 +1,286 @@
+use super::Command;
+use crate::platform::PlatformPathKind as Kind;
+use crate::platform::{Platform, PlatformPath};
+use crate::Result;
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+use structopt::clap::App;
+use structopt::StructOpt;
+use strum::IntoEnumIterator;
+
+const NAME: &str = env!("CARGO_PKG_NAME");
+
+#[derive(Debug, StructOpt)]
+#[structopt(about = "print a manual page in roff format to STDOUT")]
+pub struct ManpageCommand {
+  #[structopt(
+    value_name = "command",
+    help = "the subcommand to document, rather than platform-path itself"
+  )]
+  command: Option<String>,
+  #[structopt(
+    long = "output-dir",
+    value_name = "path",
+    conflicts_with = "command",
+    help = "write every page to this directory, rather than one page to STDOUT"
+  )]
+  output_dir: Option<PathBuf>,
+}
+
+impl ManpageCommand {
+  pub fn execute(self) -> Result<()> {
+    let Self {
+      command,
+      output_dir,
+    } = self;
+
+    if let Some(dir) = output_dir {
+      fs::create_dir_all(&dir)?;
+      for page in pages() {
+        let path = dir.join(format!("{}.1", page.title()));
+        fs::write(&path, page.render()?)?;
+        println!("{}", path.display());
+      }
+      return Ok(());
+    }
+
+    let page = pages()
+      .into_iter()
+      .find(|page| page.command == command)
+      .ok_or_else(|| {
+        let command = command.unwrap_or_default();
+        crate::Error::InvalidRequest(format!("no manual page for command: {command}"))
+      })?;
+    print!("{}", page.render()?);
+
+    Ok(())
+  }
+}
+
+struct Page {
+  command: Option<String>,
+  app: App<'static, 'static>,
+  /// Whether the page documents the paths that can be printed.
+  paths: bool,
+}
+
+impl Page {
+  fn title(&self) -> String {
+    match &self.command {
+      Some(command) => format!("{NAME}-{command}"),
+      None => NAME.to_string(),
+    }
+  }
+
+  fn render(&self) -> Result<String> {
+    let title = self.title();
+    let bin = match &self.command {
+      Some(command) => format!("{NAME} {command}"),
+      None => NAME.to_string(),
+    };
+    let app = self.app.clone().bin_name(bin).set_term_width(80);
+    let version = env!("CARGO_PKG_VERSION");
+
+    let mut roff = vec![
+      format!(
+        ".TH \"{}\" \"1\" \"\" \"{NAME} {version}\" \"User Commands\"",
+        escape(&title.to_uppercase())
+      ),
+      ".SH NAME".to_string(),
+      format!("{} \\- {}", escape(&title), escape(&help(&app, "{about}")?)),
+      ".SH SYNOPSIS".to_string(),
+      preformatted(&help(&app, "{usage}")?),
+      ".SH OPTIONS".to_string(),
+      preformatted(&help(&app, "{all-args}")?),
+    ];
+
+    if self.paths {
+      roff.push(".SH PATHS".to_string());
+      for kind in Kind::iter() {
+        roff.extend(paths(&kind)?);
+      }
+      roff.push(".SH PLATFORM SUPPORT".to_string());
+      roff.push(preformatted(&support()));
+    }
+
+    roff.push(".SH SEE ALSO".to_string());
+    let see_also: Vec<String> = pages()
+      .iter()
+      .map(Page::title)
+      .filter(|page| *page != title)
+      .map(|page| format!("\\fB{}\\fR(1)", escape(&page)))
+      .collect();
+    roff.push(see_also.join(", "));
+
+    Ok(roff.join("\n") + "\n")
+  }
+}
+
+/// The top-level page, followed by one page per subcommand, in the order
+/// they are defined.
+fn pages() -> Vec<Page> {
+  let mut pages = vec![Page {
+    command: None,
+    app: Command::clap(),
+    paths: true,
+  }];
+  pages.extend(subcommands().into_iter().map(|(name, app)| Page {
+    command: Some(name.to_string()),
+    app: app.name(name),
+    paths: name == "print",
+  }));
+  pages
+}
+
+/// Every variant of `Command`, by the name it is given on the command line.
+/// clap 2 does not expose the subcommands of an App, so they are listed here.
+fn subcommands() -> Vec<(&'static str, App<'static, 'static>)> {
+  use super::*;
+
+  #[allow(unused_mut)]
+  let mut subcommands = vec![
+    ("print", print::PrintCommand::clap()),
+    ("info", info::InfoCommand::clap()),
+    ("find", find::FindCommand::clap()),
+    ("ensure", ensure::EnsureCommand::clap()),
+    ("export", export::ExportCommand::clap()),
+    ("batch", batch::BatchCommand::clap()),
+    ("resolve", resolve::ResolveCommand::clap()),
+    ("doctor", doctor::DoctorCommand::clap()),
+    ("socket", socket::SocketCommand::clap()),
+    ("whatis", whatis::WhatisCommand::clap()),
+    ("completions", completions::CompletionsCommand::clap()),
+    ("advise", advise::AdviseCommand::clap()),
+    ("manpage", ManpageCommand::clap()),
+  ];
+  #[cfg(feature = "http")]
+  subcommands.push(("serve", serve::ServeCommand::clap()));
+  subcommands
+}
+
+/// Describes every path of a kind, using the descriptions shown in `--help`.
+fn paths(kind: &Kind) -> Result<Vec<String>> {
+  let app = kind.clap().set_term_width(0);
+  let descriptions = help(&app, "{subcommands}")?;
+  let mut roff = vec![format!(".SS {kind}"), escape(&help(&app, "{about}")?)];
+
+  for path in kind.paths() {
+    let name = path.to_string();
+    let description = descriptions
+      .lines()
+      .filter_map(|line| line.trim().split_once(char::is_whitespace))
+      .find(|(command, _)| *command == name)
+      .map(|(_, description)| description.trim().to_string())
+      .unwrap_or_default();
+    roff.push(".TP".to_string());
+    roff.push(format!("\\fB{}\\fR", escape(&name)));
+    roff.push(escape(&description));
+  }
+
+  Ok(roff)
+}
+
+/// A table of the status of every path on every platform, as listed by `info`.
+fn support() -> String {
+  let platforms: Vec<Platform> = Platform::iter().collect();
+  let rows: Vec<(String, Vec<String>)> = Kind::iter()
+    .flat_map(|kind| {
+      kind.paths().into_iter().map(move |path: PlatformPath| {
+        let statuses = Platform::iter()
+          .map(|platform| path.status(&platform).to_string())
+          .collect();
+        (format!("{kind} {path}"), statuses)
+      })
+    })
+    .collect();
+
+  let width = rows
+    .iter()
+    .map(|(path, _)| path.len())
+    .max()
+    .unwrap_or_default();
+  let column = |values: Vec<String>| -> String {
+    values
+      .iter()
+      .map(|value| format!("{value:<13}"))
+      .collect::<String>()
+      .trim_end()
+      .to_string()
+  };
+
+  let header = platforms.iter().map(Platform::to_string).collect();
+  let mut lines = vec![format!("{:<width$}  {}", "path", column(header))];
+  lines.extend(
+    rows
+      .into_iter()
+      .map(|(path, statuses)| format!("{path:<width$}  {}", column(statuses))),
+  );
+  lines.join("\n")
+}
+
+fn help(app: &App, template: &'static str) -> Result<String> {
+  let mut buffer = Vec::new();
+  app
+    .clone()
+    .template(template)
+    .write_help(&mut buffer)
+    .map_err(|error| io::Error::other(error.to_string()))?;
+  Ok(String::from_utf8_lossy(&buffer).trim_end().to_string())
+}
+
+fn preformatted(text: &str) -> String {
+  format!(".nf\n{}\n.fi", escape(text))
+}
+
+/// Escapes text so that roff prints it literally.
+fn escape(text: &str) -> String {
+  text
+    .lines()
+    .map(|line| {
+      let line = line.replace('\\', "\\e").replace('-', "\\-");
+      match line.starts_with('.') || line.starts_with('\'') {
+        true => format!("\\&{line}"),
+        false => line,
+      }
+    })
+    .collect::<Vec<String>>()
+    .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn every_subcommand_has_a_page() {
+    // The subcommands listed in `--help`, which clap builds from `Command`.
+    let listed = help(&Command::clap(), "{subcommands}").unwrap();
+    let mut listed: Vec<&str> = listed
+      .lines()
+      .filter_map(|line| line.split_whitespace().next())
+      .filter(|name| *name != "help")
+      .collect();
+    let pages = pages();
+    let commands: Vec<&str> = pages
+      .iter()
+      .filter_map(|page| page.command.as_deref())
+      .collect();
+    let mut commands = commands;
+    commands.sort_unstable();
+    listed.sort_unstable();
+    assert_eq!(commands, listed);
+  }
+
+  #[test]
+  fn every_page_has_a_name_line() {
+    for page in pages() {
+      let roff = page.render().unwrap();
+      let mut lines = roff.lines().skip_while(|line| *line != ".SH NAME").skip(1);
+      let name = lines.next().unwrap();
+      let (title, about) = name.split_once(" \\- ").unwrap();
+      assert_eq!(title, escape(&page.title()));
+      assert!(!about.is_empty() && !about.contains("unknown"), "{name}");
+    }
+  }
+}
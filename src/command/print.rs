@@ -1,5 +1,8 @@
 use crate::output::{Format, FormatOptions};
-use crate::platform::{Base, Project, ProjectOptions, StructuredPathString, User};
+use crate::platform::{
+  trash_for, Base, Fallback, Integration, OptionalProjectOptions, Project, ProjectOptions,
+  Resolved, Resolver, ResolverOptions, Source, StructuredPath, System, ToolingPath, User,
+};
 use crate::Result;
 use camino::Utf8PathBuf;
 use std::path::PathBuf;
@@ -9,11 +12,29 @@ use strum::{Display, EnumString, EnumVariantNames, VariantNames};
 #[derive(Debug, StructOpt)]
 #[structopt(about = "print path info to STDOUT")]
 pub struct PrintCommand {
-  #[structopt(long, help = "alternate path to use in case of errors")]
-  default: Option<PathBuf>,
+  #[structopt(
+    long,
+    value_name = "path",
+    help = "alternate path to use in case of errors, which may start with ~ or contain $VAR"
+  )]
+  default: Option<String>,
+  #[structopt(
+    long,
+    value_name = "policy",
+    default_value,
+    help = "what to use instead of base and project paths that the platform does not define: strict, conventional, or a comma-separated list of paths such as data-local,secure-temp"
+  )]
+  fallback: Fallback,
+  #[structopt(
+    long,
+    help = "explain which input decided the path, and which inputs were rejected"
+  )]
+  explain: bool,
   #[structopt(long, value_name = "mode", default_value, possible_values = Unicode::VARIANTS)]
   unicode: Unicode,
   #[structopt(flatten)]
+  resolver: ResolverOptions,
+  #[structopt(flatten)]
   format: FormatOptions,
   #[structopt(subcommand)]
   path: PlatformPath,
@@ -23,35 +44,63 @@ impl PrintCommand {
   pub fn execute(self) -> Result<()> {
     let Self {
       default,
+      fallback,
+      explain,
       unicode,
+      resolver,
       format: FormatOptions { format },
       path,
     } = self;
 
-    let path = match path {
-      PlatformPath::User(path) => path.path_buf(),
-      PlatformPath::Base(path) => path.path_buf(),
-      PlatformPath::Project { path, options } => path.path_buf(&options),
+    let resolver = Resolver::try_from(&resolver)?;
+    let listed = matches!(path, PlatformPath::System { .. });
+    let mut resolved = match path {
+      PlatformPath::User(path) => path.explain(&resolver).map(Resolved::listed),
+      PlatformPath::Integration(path) => path.explain(&resolver).map(Resolved::listed),
+      PlatformPath::Tooling(path) => {
+        let (path, options) = path.into();
+        path.explain(&resolver, &options).map(Resolved::listed)
+      }
+      PlatformPath::Base(path) => path
+        .explain_with(&resolver, &fallback)
+        .map(Resolved::listed),
+      PlatformPath::Project { path, options } => path
+        .explain_with(&resolver, &options, &fallback)
+        .map(Resolved::listed),
+      PlatformPath::System { path, options } => path.explain(&resolver, options.project().as_ref()),
+      PlatformPath::Trash { file: None } => Base::Trash.explain(&resolver).map(Resolved::listed),
+      PlatformPath::Trash { file: Some(file) } => trash_for(&resolver, &file).map(Resolved::listed),
+    }
+    .or_else(|err| match default {
+      Some(path) => {
+        let mut resolved = Resolved::new(vec![resolver.env.expand(&path)?], Source::Fallback);
+        resolved.fallback = Some("--default".to_string());
+        Ok(resolved)
+      }
+      None => Err(err),
+    })?;
+
+    if let (Some(fallback), Format::Text) = (&resolved.fallback, &format) {
+      eprintln!("note: could not resolve this path, using {fallback} instead");
     }
-    .or_else(|err| default.ok_or(err))?;
 
-    let path = match unicode {
-      Unicode::Required => Utf8PathBuf::try_from(path)?.to_string(),
-      Unicode::Enforced => path.to_string_lossy().to_string(),
+    let mut paths = std::mem::take(&mut resolved.path)
+      .into_iter()
+      .map(|path| unicode.encode(path))
+      .collect::<Result<Vec<String>>>()?;
+
+    let path = match listed {
+      true => StructuredPath::from(paths),
+      false => StructuredPath::from(paths.remove(0)),
     };
 
-    let path = StructuredPathString::from(path);
-    let output = match format {
-      Format::Text => path.path,
-      #[cfg(feature = "json")]
-      Format::Json => serde_json::to_string(&path)?,
-      #[cfg(feature = "json")]
-      Format::JsonPretty => serde_json::to_string_pretty(&path)?,
-      #[cfg(feature = "yaml")]
-      Format::Yaml => serde_yaml::to_string(&path)?,
+    // Structured output always names the source, text only when asked to.
+    let path = match explain || !matches!(format, Format::Text) {
+      true => path.explained(resolved),
+      false => path,
     };
 
-    println!("{output}");
+    println!("{}", path.sandboxed(&resolver).render(&format)?);
     Ok(())
   }
 }
@@ -60,23 +109,46 @@ impl PrintCommand {
 enum PlatformPath {
   User(User),
   Base(Base),
+  Integration(Integration),
+  Tooling(ToolingPath),
   Project {
     #[structopt(subcommand)]
     path: Project,
     #[structopt(flatten)]
     options: ProjectOptions,
   },
+  System {
+    #[structopt(subcommand)]
+    path: System,
+    #[structopt(flatten)]
+    options: OptionalProjectOptions,
+  },
+  #[structopt(about = "the trash directory that files are moved to")]
+  Trash {
+    #[structopt(
+      long = "for",
+      value_name = "file",
+      help = "find the trash directory on the same volume as this file"
+    )]
+    file: Option<PathBuf>,
+  },
 }
 
-#[derive(Debug, Display, EnumString, EnumVariantNames)]
+#[derive(Debug, Default, Display, EnumString, EnumVariantNames)]
 #[strum(serialize_all = "kebab-case")]
 enum Unicode {
+  #[default]
   Required,
   Enforced,
 }
 
-impl Default for Unicode {
-  fn default() -> Self {
-    Self::Required
+impl Unicode {
+  fn encode(&self, path: PathBuf) -> Result<String> {
+    let path = match self {
+      Self::Required => Utf8PathBuf::try_from(path)?.into_string(),
+      Self::Enforced => path.to_string_lossy().to_string(),
+    };
+
+    Ok(path)
   }
 }
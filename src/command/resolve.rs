@@ -0,0 +1,51 @@
+use crate::output::FormatOptions;
+use crate::platform::PlatformPathKind as Kind;
+use crate::platform::{OptionalProjectOptions, Resolution, Resolver, ResolverOptions};
+use crate::Result;
+use structopt::StructOpt;
+use strum::{IntoEnumIterator, VariantNames};
+
+#[derive(Debug, StructOpt)]
+#[structopt(about = "print every resolved path, for inclusion in bug reports or config management")]
+pub struct ResolveCommand {
+  #[structopt(long, required_unless = "kinds", help = "resolve paths of every kind")]
+  all: bool,
+  #[structopt(
+    short = "k",
+    long = "kind",
+    number_of_values = 1,
+    possible_values = Kind::VARIANTS,
+    help = "resolve paths of this kind (may be repeated)"
+  )]
+  kinds: Vec<Kind>,
+  #[structopt(flatten)]
+  project: OptionalProjectOptions,
+  #[structopt(flatten)]
+  resolver: ResolverOptions,
+  #[structopt(flatten)]
+  format: FormatOptions,
+}
+
+impl ResolveCommand {
+  pub fn execute(self) -> Result<()> {
+    let Self {
+      all,
+      kinds,
+      project,
+      resolver,
+      format: FormatOptions { format },
+    } = self;
+
+    let resolver = Resolver::try_from(&resolver)?;
+    let kinds = match all {
+      true => Kind::iter().collect(),
+      false => kinds,
+    };
+
+    let resolution = Resolution::resolve(&resolver, &kinds, project.project().as_ref());
+    let separator = resolver.platform.list_separator();
+    println!("{}", resolution.render(&format, separator)?);
+
+    Ok(())
+  }
+}
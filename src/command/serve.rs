@@ -1,6 +1,12 @@
+use crate::output::Format;
 use crate::platform::PlatformPathKind as Kind;
-use crate::{Base, Project, ProjectOptions, User};
+use crate::platform::StructuredPath;
+use crate::{
+  Base, Error, Integration, Project, ProjectOptions, Resolver, ResolverOptions, System, Tooling,
+  ToolingOptions, User,
+};
 use camino::Utf8PathBuf;
+use std::path::PathBuf;
 use structopt::StructOpt;
 
 #[cfg(feature = "http")]
@@ -10,13 +16,21 @@ use tide::{Request, Server};
 #[structopt(about = "expose path info over HTTP")]
 pub struct ServeCommand {
   #[structopt(flatten)]
-  state: ProjectOptions,
+  project: ProjectOptions,
+  #[structopt(flatten)]
+  resolver: ResolverOptions,
   #[structopt(flatten)]
   router: RouterOptions,
   #[structopt(flatten)]
   listener: ListenerOptions,
 }
 
+#[derive(Debug, Clone)]
+struct State {
+  project: ProjectOptions,
+  resolver: Resolver,
+}
+
 #[derive(Debug, StructOpt)]
 struct RouterOptions {
   #[structopt(
@@ -76,10 +90,13 @@ struct TlsOptions {
 impl ServeCommand {
   pub async fn execute(self) -> anyhow::Result<()> {
     let Self {
-      state,
+      project,
+      resolver,
       router,
       listener,
     } = self;
+    let resolver = Resolver::try_from(&resolver)?;
+    let state = State { project, resolver };
     let mut server = tide::with_state(state);
     router.route(&mut server);
     listener.listen(server).await?;
@@ -88,7 +105,7 @@ impl ServeCommand {
 }
 
 impl RouterOptions {
-  fn route(self, server: &mut Server<ProjectOptions>) {
+  fn route(self, server: &mut Server<State>) {
     let prefix = if self.prefix.starts_with('/') {
       self.prefix
     } else {
@@ -112,7 +129,7 @@ impl RouterOptions {
 
 impl ListenerOptions {
   #[cfg(feature = "https")]
-  async fn listen(self, server: Server<ProjectOptions>) -> anyhow::Result<()> {
+  async fn listen(self, server: Server<State>) -> anyhow::Result<()> {
     use tide_rustls::TlsListener;
     if self.tls.enabled {
       match (self.tls.certificate_path, self.tls.key_path) {
@@ -132,7 +149,7 @@ impl ListenerOptions {
   }
 
   #[cfg(not(feature = "https"))]
-  async fn listen(self, server: Server<ProjectOptions>) -> anyhow::Result<()> {
+  async fn listen(self, server: Server<State>) -> anyhow::Result<()> {
     server.listen(self.address).await?;
 
     Ok(())
@@ -140,34 +157,66 @@ impl ListenerOptions {
 }
 
 #[cfg(feature = "http")]
-fn handle(request: Request<ProjectOptions>) -> tide::Result<Utf8PathBuf> {
+fn handle(request: Request<State>, format: Format) -> tide::Result {
   let path = request.param("path")?;
-  let path = match request.param("kind")?.parse::<Kind>()? {
-    Kind::Base => path.parse::<Base>()?.utf8_path_buf(),
-    Kind::User => path.parse::<User>()?.utf8_path_buf(),
-    Kind::Project => path.parse::<Project>()?.utf8_path_buf(request.state()),
-  }?;
-  Ok(path)
+  let State { project, resolver } = request.state();
+  let utf8 = |path: PathBuf| Utf8PathBuf::try_from(path).map(String::from);
+  let kind = request.param("kind")?.parse::<Kind>()?;
+  let mut resolved = match kind {
+    Kind::Base => path.parse::<Base>()?.explain(resolver)?.listed(),
+    Kind::User => path.parse::<User>()?.explain(resolver)?.listed(),
+    Kind::Project => path
+      .parse::<Project>()?
+      .explain(resolver, project)?
+      .listed(),
+    Kind::System => path.parse::<System>()?.explain(resolver, None)?,
+    Kind::Integration => path.parse::<Integration>()?.explain(resolver)?.listed(),
+    Kind::Tooling => {
+      let mut options = ToolingOptions::default();
+      for (key, value) in request.url().query_pairs() {
+        match key.as_ref() {
+          "shell" => options.shell = value.parse()?,
+          "section" => options.section = value.into_owned(),
+          _ => (),
+        }
+      }
+      // An invalid query is the client's fault, rather than the server's.
+      path
+        .parse::<Tooling>()?
+        .explain(resolver, &options)
+        .map_err(|error| match error {
+          Error::InvalidRequest(_) => tide::Error::new(tide::StatusCode::BadRequest, error),
+          error => error.into(),
+        })?
+        .listed()
+    }
+  };
+  let mut paths = std::mem::take(&mut resolved.path)
+    .into_iter()
+    .map(utf8)
+    .collect::<Result<Vec<_>, _>>()?;
+  let path = match kind {
+    Kind::System => StructuredPath::from(paths),
+    _ => StructuredPath::from(paths.remove(0)),
+  };
+  let path = match !matches!(format, Format::Text) {
+    true => path.explained(resolved),
+    false => path,
+  };
+  Ok(path.sandboxed(resolver).render(&format)?.into())
 }
 
 #[cfg(feature = "http")]
-async fn text(request: Request<ProjectOptions>) -> tide::Result {
-  let path = handle(request)?;
-  Ok(path.into_string().into())
+async fn text(request: Request<State>) -> tide::Result {
+  handle(request, Format::Text)
 }
 
 #[cfg(all(feature = "http", feature = "json"))]
-async fn json(request: Request<ProjectOptions>) -> tide::Result {
-  use crate::platform::StructuredPathString;
-
-  let path: String = handle(request)?.into();
-  Ok(serde_json::to_string(&StructuredPathString::from(path))?.into())
+async fn json(request: Request<State>) -> tide::Result {
+  handle(request, Format::Json)
 }
 
 #[cfg(all(feature = "http", feature = "yaml"))]
-async fn yaml(request: Request<ProjectOptions>) -> tide::Result {
-  use crate::platform::StructuredPathString;
-
-  let path: String = handle(request)?.into();
-  Ok(serde_yaml::to_string(&StructuredPathString::from(path))?.into())
+async fn yaml(request: Request<State>) -> tide::Result {
+  handle(request, Format::Yaml)
 }
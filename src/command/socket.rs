@@ -0,0 +1,74 @@
+use crate::output::{Format, FormatOptions};
+use crate::platform::{
+  socket, Fallback, ProjectOptions, Resolver, ResolverOptions, Socket, StructuredSocket,
+};
+use crate::Result;
+use camino::Utf8PathBuf;
+use structopt::StructOpt;
+
+#[derive(Debug, StructOpt)]
+#[structopt(about = "print the path of a Unix domain socket in the project's runtime directory")]
+pub struct SocketCommand {
+  #[structopt(help = "the file name of the socket")]
+  name: String,
+  #[structopt(long, help = "report the length limit, and the path that was too long")]
+  explain: bool,
+  #[structopt(
+    long,
+    value_name = "policy",
+    default_value,
+    help = "what to use if the platform does not define a runtime directory: strict, conventional, or a comma-separated list of paths such as secure-temp"
+  )]
+  fallback: Fallback,
+  #[structopt(flatten)]
+  options: ProjectOptions,
+  #[structopt(flatten)]
+  resolver: ResolverOptions,
+  #[structopt(flatten)]
+  format: FormatOptions,
+}
+
+impl SocketCommand {
+  pub fn execute(self) -> Result<()> {
+    let Self {
+      name,
+      explain,
+      fallback,
+      options,
+      resolver,
+      format: FormatOptions { format },
+    } = self;
+
+    let resolver = Resolver::try_from(&resolver)?;
+    let Socket {
+      path,
+      limit,
+      original,
+    } = socket(&resolver, &options, &name, &fallback)?;
+
+    // Structured output reports the same as the `original` and `fallback`
+    // fields.
+    if matches!(format, Format::Text) {
+      if let Some(original) = &original {
+        eprintln!(
+          "note: {} is not shorter than the {limit}-byte limit, using a hashed path instead",
+          original.display()
+        );
+      } else if let Some(fallback) = &path.fallback {
+        eprintln!(
+          "note: the platform standard does not define a runtime directory, using {fallback} instead"
+        );
+      }
+    }
+
+    let socket = StructuredSocket {
+      path: Utf8PathBuf::try_from(path.path)?.into_string(),
+      limit,
+      original: original.map(|path| path.to_string_lossy().into_owned()),
+      fallback: path.fallback,
+    };
+    println!("{}", socket.render(&format, explain)?);
+
+    Ok(())
+  }
+}
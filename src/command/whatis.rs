@@ -0,0 +1,117 @@
+use crate::output::FormatOptions;
+use crate::platform::{whatis, Platform, Resolver, ResolverOptions, StructuredClassification};
+use crate::Result;
+use std::env;
+use std::io::{self, BufRead};
+use std::path::PathBuf;
+use structopt::StructOpt;
+
+#[derive(Debug, StructOpt)]
+#[structopt(
+  about = "print which standard directory a path lives in",
+  long_about = "print which standard directory a path lives in
+
+Every base, user and project directory is checked, and the project is guessed from the path. Paths are read from STDIN, one per line, if none are given. Each path produces one line of output."
+)]
+pub struct WhatisCommand {
+  #[structopt(value_name = "path")]
+  paths: Vec<PathBuf>,
+  #[structopt(flatten)]
+  resolver: ResolverOptions,
+  #[structopt(flatten)]
+  format: FormatOptions,
+}
+
+impl WhatisCommand {
+  pub fn execute(self) -> Result<()> {
+    let Self {
+      paths,
+      resolver,
+      format: FormatOptions { format },
+    } = self;
+
+    let resolver = Resolver::try_from(&resolver)?;
+    let format = format.single_line();
+    let paths = match paths.is_empty() {
+      true => read_paths(io::stdin().lock())?,
+      false => paths,
+    };
+
+    // Relative paths only make sense for the host, where they are relative
+    // to the current directory.
+    let current = match resolver.platform == Platform::default() {
+      true => Some(env::current_dir()?),
+      false => None,
+    };
+
+    for path in paths {
+      let absolute = match &current {
+        Some(current) => current.join(&path),
+        None => path.clone(),
+      };
+      let classification = whatis(&resolver, &absolute);
+      let path = path.to_string_lossy().into_owned();
+      let classification = StructuredClassification::new(path, classification);
+
+      println!("{}", classification.render(&format)?);
+    }
+
+    Ok(())
+  }
+}
+
+/// Reads one path per line, skipping blank lines.
+fn read_paths(input: impl BufRead) -> Result<Vec<PathBuf>> {
+  let mut paths = Vec::new();
+  for line in input.split(b'\n') {
+    let line = line?;
+    let line = line.trim_ascii();
+    if !line.is_empty() {
+      paths.push(path(line));
+    }
+  }
+  Ok(paths)
+}
+
+/// Paths are bytes on Unix, so a line that is not valid UTF-8 is kept as it
+/// is, rather than stopping the stream.
+#[cfg(unix)]
+fn path(line: &[u8]) -> PathBuf {
+  use std::ffi::OsStr;
+  use std::os::unix::ffi::OsStrExt;
+
+  PathBuf::from(OsStr::from_bytes(line))
+}
+
+#[cfg(not(unix))]
+fn path(line: &[u8]) -> PathBuf {
+  PathBuf::from(String::from_utf8_lossy(line).into_owned())
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn reads_one_path_per_line() {
+    let paths = read_paths(&b"/home/alice/.cache\n\n  relative/path  \n"[..]).unwrap();
+    assert_eq!(
+      paths,
+      vec![
+        PathBuf::from("/home/alice/.cache"),
+        PathBuf::from("relative/path")
+      ]
+    );
+  }
+
+  #[test]
+  #[cfg(unix)]
+  fn keeps_lines_that_are_not_utf8() {
+    use std::os::unix::ffi::OsStrExt;
+
+    let paths = read_paths(&b"/tmp/caf\xe9\n/tmp/next\n"[..]).unwrap();
+    assert_eq!(paths.len(), 2);
+    assert_eq!(paths[0].as_os_str().as_bytes(), b"/tmp/caf\xe9");
+    assert_eq!(paths[1], PathBuf::from("/tmp/next"));
+  }
+}
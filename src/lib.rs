@@ -9,6 +9,33 @@ pub enum Error {
   InvalidHomeDirectory,
   #[error("platform standard does not define requested directory")]
   NotDefinedByPlatformStandard,
+  #[error("ignored {0}, and the platform standard does not define a default")]
+  RejectedInput(Rejected),
+  #[error("no matching file found in search directories")]
+  NotFound,
+  #[error("unrecognized path name: {0}")]
+  InvalidPathName(String),
+  #[error("invalid request: {0}")]
+  InvalidRequest(String),
+  #[error("cannot modify the filesystem for a simulated platform: {0}")]
+  SimulatedPlatform(Platform),
+  #[error("refusing to use {}: {reason}", path.display())]
+  UnsafeDirectory {
+    path: std::path::PathBuf,
+    reason: String,
+  },
+  #[error("socket path {} is not shorter than the {limit}-byte limit", path.display())]
+  SocketPathTooLong {
+    path: std::path::PathBuf,
+    limit: usize,
+  },
+  #[error("io error: {0}")]
+  IoError(#[from] std::io::Error),
+  #[error("unable to read environment file {}: {error}", path.display())]
+  EnvironmentFile {
+    path: std::path::PathBuf,
+    error: std::io::Error,
+  },
   #[error("path contains invalid unicode: {0}")]
   InvalidUnicode(#[from] camino::FromPathBufError),
   #[cfg(feature = "json")]
@@ -22,4 +49,9 @@ pub enum Error {
 pub type Result<T> = std::result::Result<T, Error>;
 
 pub use command::Command;
-pub use platform::{Base, Project, ProjectOptions, User};
+pub use platform::{
+  find, secure_temp, socket, trash_for, whatis, Advice, Base, Characteristics, Classification,
+  CompletionShell, Environment, Fallback, Integration, OptionalProjectOptions, Platform, Project,
+  ProjectOptions, Rejected, Resolved, Resolver, ResolverOptions, Sandbox, Scope, Socket, Source,
+  System, Tooling, ToolingOptions, User,
+};
@@ -7,9 +7,10 @@ pub(crate) struct FormatOptions {
   pub(crate) format: Format,
 }
 
-#[derive(Debug, Display, EnumString, EnumVariantNames)]
+#[derive(Debug, Default, Display, EnumString, EnumVariantNames)]
 #[strum(serialize_all = "kebab-case")]
 pub(crate) enum Format {
+  #[default]
   Text,
   #[cfg(feature = "json")]
   Json,
@@ -19,8 +20,93 @@ pub(crate) enum Format {
   Yaml,
 }
 
-impl Default for Format {
-  fn default() -> Self {
-    Self::Text
+impl Format {
+  /// The format to use when every record is printed on a line of its own.
+  pub(crate) fn single_line(self) -> Self {
+    match self {
+      #[cfg(feature = "json")]
+      Self::JsonPretty => Self::Json,
+      format => format,
+    }
+  }
+}
+
+/// Types that can be printed in every format. Structured formats need
+/// `serde`, so without it this asks nothing of the type.
+#[cfg(feature = "serde1")]
+pub(crate) trait Structured: serde::Serialize {}
+#[cfg(feature = "serde1")]
+impl<T: serde::Serialize> Structured for T {}
+
+#[cfg(not(feature = "serde1"))]
+pub(crate) trait Structured {}
+#[cfg(not(feature = "serde1"))]
+impl<T> Structured for T {}
+
+impl Format {
+  /// Serializes `value` in a structured format, or renders it as text with
+  /// `text`.
+  #[cfg_attr(not(any(feature = "json", feature = "yaml")), allow(unused_variables))]
+  pub(crate) fn render<T, F>(&self, value: &T, text: F) -> crate::Result<String>
+  where
+    T: Structured,
+    F: FnOnce() -> crate::Result<String>,
+  {
+    match self {
+      #[cfg(feature = "json")]
+      Self::Json => Ok(serde_json::to_string(value)?),
+      #[cfg(feature = "json")]
+      Self::JsonPretty => Ok(serde_json::to_string_pretty(value)?),
+      #[cfg(feature = "yaml")]
+      Self::Yaml => Ok(serde_yaml::to_string(value)?),
+      Self::Text => text(),
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::Error;
+
+  const VALUE: [&str; 2] = ["cache", "config"];
+
+  #[test]
+  fn text_is_rendered_by_the_closure() {
+    let rendered = Format::Text.render(&VALUE, || Ok("cache config".to_string()));
+    assert_eq!(rendered.unwrap(), "cache config");
+    let failed = Format::Text.render(&VALUE, || Err(Error::NotFound));
+    assert!(matches!(failed, Err(Error::NotFound)));
+  }
+
+  #[test]
+  #[cfg(feature = "json")]
+  fn json_serializes_the_value() {
+    let text = || -> crate::Result<String> { panic!("structured formats do not render text") };
+    assert_eq!(
+      Format::Json.render(&VALUE, text).unwrap(),
+      r#"["cache","config"]"#
+    );
+    assert_eq!(
+      Format::JsonPretty.render(&VALUE, text).unwrap(),
+      "[\n  \"cache\",\n  \"config\"\n]"
+    );
+  }
+
+  #[test]
+  #[cfg(feature = "yaml")]
+  fn yaml_serializes_the_value() {
+    let text = || -> crate::Result<String> { panic!("structured formats do not render text") };
+    assert_eq!(
+      Format::Yaml.render(&VALUE, text).unwrap(),
+      "---\n- cache\n- config\n"
+    );
+  }
+
+  #[test]
+  fn single_line_formats_are_kept() {
+    assert!(matches!(Format::Text.single_line(), Format::Text));
+    #[cfg(feature = "json")]
+    assert!(matches!(Format::JsonPretty.single_line(), Format::Json));
   }
 }
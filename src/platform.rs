@@ -1,15 +1,43 @@
+mod account;
+mod advice;
+mod attribute;
+mod diagnosis;
+mod ensure;
+mod environment;
+mod fallback;
 mod info;
 mod path;
+mod resolution;
+mod resolver;
+mod sandbox;
+mod socket;
+mod source;
 mod status;
+#[cfg(test)]
+mod testing;
+mod trash;
+mod whatis;
 
+pub use advice::*;
+pub(crate) use attribute::*;
+pub(crate) use diagnosis::*;
+pub use environment::*;
+pub use fallback::*;
 pub(crate) use info::*;
 pub use path::*;
+pub(crate) use resolution::*;
+pub use resolver::*;
+pub use sandbox::*;
+pub use socket::*;
+pub use source::*;
 pub(crate) use status::*;
+pub use trash::*;
+pub use whatis::*;
 
 use strum::{Display, EnumIter, EnumString, EnumVariantNames, IntoStaticStr};
 
 #[derive(
-  Debug, PartialEq, Eq, Hash, Display, EnumString, EnumVariantNames, EnumIter, IntoStaticStr,
+  Debug, Clone, PartialEq, Eq, Hash, Display, EnumString, EnumVariantNames, EnumIter, IntoStaticStr,
 )]
 #[strum(serialize_all = "lowercase")]
 #[cfg_attr(
@@ -17,7 +45,7 @@ use strum::{Display, EnumIter, EnumString, EnumVariantNames, IntoStaticStr};
   derive(serde::Serialize, serde::Deserialize),
   serde(rename_all = "lowercase")
 )]
-pub(crate) enum Platform {
+pub enum Platform {
   Linux,
   MacOS,
   Windows,
@@ -51,6 +79,7 @@ impl Default for Platform {
   target_os = "ios",
   target_arch = "wasm32"
 )))]
+#[allow(clippy::derivable_impls)]
 impl Default for Platform {
   fn default() -> Self {
     Self::Linux
@@ -0,0 +1,141 @@
+use std::ffi::OsString;
+use std::path::PathBuf;
+
+/// The current user's entry in the passwd database.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Account {
+  pub(crate) name: OsString,
+  pub(crate) home: PathBuf,
+  pub(crate) gid: u32,
+}
+
+/// An entry in the group database.
+#[cfg(unix)]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Group {
+  pub(crate) name: OsString,
+  pub(crate) members: Vec<OsString>,
+}
+
+/// The real user id of the current process.
+#[cfg(unix)]
+pub(crate) fn uid() -> u32 {
+  // getuid always succeeds, and has no preconditions.
+  unsafe { libc::getuid() }
+}
+
+/// The current user's account, or None if the passwd database has no entry
+/// for their uid.
+#[cfg(unix)]
+pub(crate) fn account() -> Option<Account> {
+  lookup(|buffer| {
+    let mut entry = std::mem::MaybeUninit::<libc::passwd>::uninit();
+    let mut result = std::ptr::null_mut();
+    let status = unsafe {
+      libc::getpwuid_r(
+        uid(),
+        entry.as_mut_ptr(),
+        buffer.as_mut_ptr(),
+        buffer.len(),
+        &mut result,
+      )
+    };
+    if status != 0 || result.is_null() {
+      return (status, None);
+    }
+    // The strings point into the buffer, so they are copied out before it is
+    // reused or dropped.
+    let entry = unsafe { entry.assume_init() };
+    let account =
+      unsafe { owned(entry.pw_name).zip(owned(entry.pw_dir)) }.map(|(name, home)| Account {
+        name,
+        home: PathBuf::from(home),
+        gid: entry.pw_gid,
+      });
+    (status, account)
+  })
+}
+
+/// The group with this gid, or None if the group database has no entry for
+/// it.
+#[cfg(unix)]
+pub(crate) fn group(gid: u32) -> Option<Group> {
+  lookup(|buffer| {
+    let mut entry = std::mem::MaybeUninit::<libc::group>::uninit();
+    let mut result = std::ptr::null_mut();
+    let status = unsafe {
+      libc::getgrgid_r(
+        gid,
+        entry.as_mut_ptr(),
+        buffer.as_mut_ptr(),
+        buffer.len(),
+        &mut result,
+      )
+    };
+    if status != 0 || result.is_null() {
+      return (status, None);
+    }
+    let entry = unsafe { entry.assume_init() };
+    let mut members = Vec::new();
+    let mut member = entry.gr_mem;
+    while !member.is_null() {
+      match unsafe { owned(*member) } {
+        Some(name) => members.push(name),
+        None => break,
+      }
+      member = unsafe { member.add(1) };
+    }
+    let group = unsafe { owned(entry.gr_name) }.map(|name| Group { name, members });
+    (status, group)
+  })
+}
+
+/// Calls a reentrant lookup such as getpwuid_r with a buffer for the strings
+/// of the entry, growing the buffer for as long as the entry does not fit.
+#[cfg(unix)]
+fn lookup<T>(mut call: impl FnMut(&mut [libc::c_char]) -> (libc::c_int, Option<T>)) -> Option<T> {
+  const LIMIT: usize = 1 << 20;
+
+  let mut buffer = vec![0; 1024];
+  loop {
+    match call(&mut buffer) {
+      (libc::ERANGE, _) if buffer.len() < LIMIT => buffer.resize(buffer.len() * 2, 0),
+      (_, entry) => return entry,
+    }
+  }
+}
+
+/// Copies a NUL-terminated string, which may be null.
+#[cfg(unix)]
+unsafe fn owned(string: *const libc::c_char) -> Option<OsString> {
+  use std::ffi::{CStr, OsStr};
+  use std::os::unix::ffi::OsStrExt;
+
+  match string.is_null() {
+    true => None,
+    false => Some(OsStr::from_bytes(CStr::from_ptr(string).to_bytes()).to_os_string()),
+  }
+}
+
+#[cfg(not(unix))]
+pub(crate) fn account() -> Option<Account> {
+  None
+}
+
+#[cfg(test)]
+#[cfg(unix)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn the_account_matches_the_current_user() {
+    // Containers do not always have a passwd entry for the user.
+    if let Some(account) = account() {
+      assert!(!account.name.is_empty());
+      assert!(account.home.is_absolute());
+      if let Some(group) = group(account.gid) {
+        assert!(!group.name.is_empty());
+      }
+    }
+  }
+}
@@ -0,0 +1,288 @@
+use super::{Attribute, Base, PlatformPath, Project, ProjectOptions, Resolver, Status};
+use crate::output::Format;
+use crate::{Error, Result};
+use std::path::PathBuf;
+use structopt::StructOpt;
+
+// A description of the data to be stored, used to recommend a directory. This
+// is a plain comment, as structopt would use a doc comment as the description
+// of the advise command.
+#[derive(Debug, Default, Clone, StructOpt)]
+pub struct Characteristics {
+  #[structopt(
+    long,
+    help = "the data can be regenerated if it is lost, such as thumbnails"
+  )]
+  pub regenerable: bool,
+  #[structopt(long, help = "the data is too large to be copied between machines")]
+  pub large: bool,
+  #[structopt(
+    long = "per-machine",
+    help = "the data is specific to this machine, such as a window layout or history"
+  )]
+  pub per_machine: bool,
+  #[structopt(long, help = "the data is secret, such as a token")]
+  pub secret: bool,
+  #[structopt(long, help = "the data is a log")]
+  pub log: bool,
+  #[structopt(
+    long,
+    help = "the data is only needed while the program runs, such as a lock or socket"
+  )]
+  pub transient: bool,
+  #[structopt(long, help = "the data is settings, which the user may edit")]
+  pub settings: bool,
+}
+
+/// A recommended directory, along with the reasons for choosing it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Advice {
+  pub path: Base,
+  /// Where the directory resolved to, or its project directory if project
+  /// options were given.
+  pub resolved: PathBuf,
+  pub reasons: Vec<String>,
+  /// Better matches that were skipped, in order of preference.
+  pub rejected: Vec<(Base, String)>,
+}
+
+impl Characteristics {
+  /// Recommends the first directory, in order of preference, that the
+  /// platform defines, whose attributes suit the data, and that resolves.
+  /// Directories that depend on the environment, such as the runtime
+  /// directory, are skipped when the environment does not provide them.
+  pub fn advise(&self, resolver: &Resolver, project: Option<&ProjectOptions>) -> Result<Advice> {
+    let platform = &resolver.platform;
+    let (candidates, mut reasons) = self.candidates();
+    let mut rejected = Vec::new();
+
+    for candidate in candidates {
+      let path = PlatformPath::from(candidate);
+      if path.status(platform) != Status::Supported {
+        rejected.push((candidate, format!("not defined on {platform}")));
+        continue;
+      }
+
+      if let Some(reason) = self.unsuitable(&path.attributes(platform)) {
+        rejected.push((candidate, reason));
+        continue;
+      }
+
+      let resolved = match project {
+        Some(options) => candidate
+          .project()
+          .ok_or(Error::NotDefinedByPlatformStandard)
+          .and_then(|path| path.resolve(resolver, options)),
+        None => candidate.resolve(resolver),
+      };
+      let resolved = match resolved {
+        Ok(resolved) => resolved,
+        Err(error) => {
+          rejected.push((candidate, format!("could not be resolved: {error}")));
+          continue;
+        }
+      };
+
+      if self.large {
+        reasons.push("the data is large, so it should not be copied between machines".to_string());
+      }
+      if self.secret {
+        reasons.push(
+          "the data is secret, so credentials are better kept in the platform's keychain"
+            .to_string(),
+        );
+      }
+      return Ok(Advice {
+        path: candidate,
+        resolved,
+        reasons,
+        rejected,
+      });
+    }
+
+    Err(Error::NotDefinedByPlatformStandard)
+  }
+
+  /// The directories suited to the most specific characteristic, in order of
+  /// preference.
+  fn candidates(&self) -> (Vec<Base>, Vec<String>) {
+    let (candidates, reason) = if self.transient {
+      (
+        vec![Base::Runtime, Base::Cache],
+        "the data is only needed while the program runs",
+      )
+    } else if self.log {
+      (
+        vec![Base::Log, Base::State, Base::DataLocal],
+        "the data is a log",
+      )
+    } else if self.regenerable {
+      (
+        vec![Base::Cache],
+        "the data can be regenerated, so it need not be backed up",
+      )
+    } else if self.settings {
+      (
+        vec![Base::Config, Base::DataLocal],
+        "the data is settings, which the user may edit",
+      )
+    } else if self.per_machine {
+      (
+        vec![Base::State, Base::DataLocal],
+        "the data is specific to this machine",
+      )
+    } else {
+      (vec![Base::Data, Base::DataLocal], "the data must be kept")
+    };
+    (candidates, vec![reason.to_string()])
+  }
+
+  /// Why a directory with these attributes does not suit the data.
+  fn unsuitable(&self, attributes: &[Attribute]) -> Option<String> {
+    if attributes.contains(&Attribute::Roams) {
+      let reason = match (self.per_machine, self.large, self.secret) {
+        (true, _, _) => Some("the data is specific to this machine"),
+        (_, true, _) => Some("the data is too large"),
+        (_, _, true) => Some("the data is secret"),
+        _ => None,
+      };
+      if let Some(reason) = reason {
+        return Some(format!("copied between machines, but {reason}"));
+      }
+    }
+
+    let disposable = self.regenerable || self.transient;
+    if attributes.contains(&Attribute::OsMayPurge) && !disposable {
+      return Some("may be emptied by the system, but the data cannot be regenerated".to_string());
+    }
+
+    None
+  }
+}
+
+impl Advice {
+  /// The project directory corresponding to the recommended directory.
+  pub fn project(&self) -> Option<Project> {
+    self.path.project()
+  }
+}
+
+#[derive(Debug)]
+#[cfg_attr(feature = "serde1", derive(serde::Serialize, serde::Deserialize))]
+pub(crate) struct StructuredAlternative {
+  pub(crate) directory: String,
+  pub(crate) reason: String,
+}
+
+#[derive(Debug)]
+#[cfg_attr(feature = "serde1", derive(serde::Serialize, serde::Deserialize))]
+pub(crate) struct StructuredAdvice {
+  pub(crate) path: String,
+  pub(crate) directory: String,
+  pub(crate) reasons: Vec<String>,
+  #[cfg_attr(
+    feature = "serde1",
+    serde(default, skip_serializing_if = "Vec::is_empty")
+  )]
+  pub(crate) rejected: Vec<StructuredAlternative>,
+}
+
+impl StructuredAdvice {
+  /// Describes the advice in terms of base directories, or project
+  /// directories if `project` is set.
+  pub(crate) fn new(advice: Advice, path: String, project: bool) -> Self {
+    let directory = |path: Base| match project {
+      true => format!("project {path}"),
+      false => format!("base {path}"),
+    };
+    Self {
+      path,
+      directory: directory(advice.path),
+      reasons: advice.reasons,
+      rejected: advice
+        .rejected
+        .into_iter()
+        .map(|(path, reason)| StructuredAlternative {
+          directory: directory(path),
+          reason,
+        })
+        .collect(),
+    }
+  }
+
+  pub(crate) fn render(&self, format: &Format) -> Result<String> {
+    format.render(self, || {
+      let mut lines = vec![self.path.clone(), format!("directory: {}", self.directory)];
+      lines.extend(
+        self
+          .reasons
+          .iter()
+          .map(|reason| format!("reason: {reason}")),
+      );
+      lines.extend(
+        self
+          .rejected
+          .iter()
+          .map(|StructuredAlternative { directory, reason }| {
+            format!("rejected: {directory} ({reason})")
+          }),
+      );
+      Ok(lines.join("\n"))
+    })
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::platform::testing::project_options;
+  use crate::platform::{Environment, Platform};
+
+  fn transient() -> Characteristics {
+    Characteristics {
+      transient: true,
+      ..Characteristics::default()
+    }
+  }
+
+  #[test]
+  fn transient_data_uses_the_runtime_directory() {
+    let env = Environment::new("/home/alice").with_var("XDG_RUNTIME_DIR", "/run/user/1000");
+    let advice = transient()
+      .advise(&Resolver::new(Platform::Linux, env), None)
+      .unwrap();
+    assert_eq!(advice.path, Base::Runtime);
+    assert_eq!(advice.resolved, PathBuf::from("/run/user/1000"));
+    assert!(advice.rejected.is_empty());
+  }
+
+  #[test]
+  fn unresolved_directories_fall_back_to_the_next_candidate() {
+    let resolver = Resolver::new(Platform::Linux, Environment::new("/home/alice"));
+    let advice = transient().advise(&resolver, None).unwrap();
+    assert_eq!(advice.path, Base::Cache);
+    assert_eq!(advice.resolved, PathBuf::from("/home/alice/.cache"));
+    assert_eq!(advice.rejected[0].0, Base::Runtime);
+  }
+
+  #[test]
+  fn undefined_directories_fall_back_to_the_next_candidate() {
+    let resolver = Resolver::new(Platform::MacOS, Environment::new("/Users/alice"));
+    let characteristics = Characteristics {
+      per_machine: true,
+      ..Characteristics::default()
+    };
+    let options = project_options("Nifty");
+    let advice = characteristics.advise(&resolver, Some(&options)).unwrap();
+    assert_eq!(advice.path, Base::DataLocal);
+    assert_eq!(advice.project(), Some(Project::DataLocal));
+    assert_eq!(
+      advice.resolved,
+      PathBuf::from("/Users/alice/Library/Application Support/Nifty")
+    );
+    assert_eq!(
+      advice.rejected,
+      vec![(Base::State, "not defined on macos".to_string())]
+    );
+  }
+}
@@ -0,0 +1,137 @@
+use strum::{Display, EnumIter, EnumString, EnumVariantNames, IntoEnumIterator};
+
+/// How the platform treats the contents of a directory.
+#[derive(
+  Debug,
+  Clone,
+  Copy,
+  PartialEq,
+  Eq,
+  PartialOrd,
+  Ord,
+  Hash,
+  Display,
+  EnumString,
+  EnumIter,
+  EnumVariantNames,
+)]
+#[strum(serialize_all = "kebab-case")]
+#[cfg_attr(
+  feature = "serde1",
+  derive(serde::Serialize, serde::Deserialize),
+  serde(rename_all = "kebab-case")
+)]
+pub(crate) enum Attribute {
+  /// Included by the platform's backup tools, such as Time Machine, or by
+  /// convention on platforms without one.
+  BackedUp,
+  /// Copied between machines as part of a Windows roaming profile.
+  Roams,
+  /// May be emptied by the system, such as when disk space runs low or the
+  /// user logs out.
+  OsMayPurge,
+  /// Shown to the user in file managers and dialogs.
+  UserVisible,
+  /// Moved elsewhere for sandboxed applications, such as Flatpak, Snap or
+  /// the macOS App Sandbox.
+  SandboxRedirected,
+}
+
+impl Attribute {
+  /// Parses a comma-separated list, as used by the `*_attributes` props.
+  /// The props are fixed at compile time, so an unknown name is a bug rather
+  /// than something to skip.
+  pub(crate) fn parse_list(attributes: &str) -> Vec<Self> {
+    attributes
+      .split(',')
+      .map(str::trim)
+      .filter(|attribute| !attribute.is_empty())
+      .map(|attribute| {
+        attribute
+          .parse()
+          .unwrap_or_else(|_| panic!("unknown attribute: {attribute}"))
+      })
+      .collect()
+  }
+}
+
+/// Every attribute as an explicit flag, so that structured output states
+/// whether a path has each one.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(
+  feature = "serde1",
+  derive(serde::Serialize, serde::Deserialize),
+  serde(rename_all = "kebab-case")
+)]
+pub(crate) struct Attributes {
+  pub(crate) backed_up: bool,
+  pub(crate) roams: bool,
+  pub(crate) os_may_purge: bool,
+  pub(crate) user_visible: bool,
+  pub(crate) sandbox_redirected: bool,
+}
+
+impl Attributes {
+  fn flag(&mut self, attribute: &Attribute) -> &mut bool {
+    match attribute {
+      Attribute::BackedUp => &mut self.backed_up,
+      Attribute::Roams => &mut self.roams,
+      Attribute::OsMayPurge => &mut self.os_may_purge,
+      Attribute::UserVisible => &mut self.user_visible,
+      Attribute::SandboxRedirected => &mut self.sandbox_redirected,
+    }
+  }
+
+  /// The attributes that are set, in the order they are declared.
+  pub(crate) fn set(&self) -> Vec<Attribute> {
+    let mut attributes = *self;
+    Attribute::iter()
+      .filter(|attribute| *attributes.flag(attribute))
+      .collect()
+  }
+}
+
+impl FromIterator<Attribute> for Attributes {
+  fn from_iter<I: IntoIterator<Item = Attribute>>(attributes: I) -> Self {
+    let mut flags = Self::default();
+    for attribute in attributes {
+      *flags.flag(&attribute) = true;
+    }
+    flags
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn lists_are_parsed_in_order() {
+    assert_eq!(
+      Attribute::parse_list("backed-up, sandbox-redirected"),
+      vec![Attribute::BackedUp, Attribute::SandboxRedirected]
+    );
+    assert_eq!(Attribute::parse_list(""), vec![]);
+  }
+
+  #[test]
+  #[should_panic(expected = "unknown attribute: backed-upp")]
+  fn unknown_names_are_a_bug() {
+    Attribute::parse_list("roams,backed-upp");
+  }
+
+  #[test]
+  fn every_attribute_has_a_flag() {
+    let attributes: Attributes = Attribute::iter().collect();
+    assert_eq!(attributes.set(), Attribute::iter().collect::<Vec<_>>());
+    let attributes: Attributes = [Attribute::Roams].into_iter().collect();
+    assert_eq!(
+      attributes,
+      Attributes {
+        roams: true,
+        ..Attributes::default()
+      }
+    );
+    assert_eq!(attributes.set(), vec![Attribute::Roams]);
+  }
+}
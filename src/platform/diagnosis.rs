@@ -0,0 +1,273 @@
+use super::account::account;
+use super::ensure::{verify, Permissions};
+use super::{
+  Base, Platform, PlatformPath, Project, ProjectOptions, Resolved, Resolver, Status, User,
+};
+use crate::output::Format;
+use crate::{Error, Result};
+use std::fs;
+use std::path::Path;
+use strum::{Display, IntoEnumIterator};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Display)]
+#[strum(serialize_all = "lowercase")]
+#[cfg_attr(
+  feature = "serde1",
+  derive(serde::Serialize, serde::Deserialize),
+  serde(rename_all = "lowercase")
+)]
+pub(crate) enum Severity {
+  Pass,
+  Warn,
+  Fail,
+}
+
+#[derive(Debug)]
+#[cfg_attr(feature = "serde1", derive(serde::Serialize, serde::Deserialize))]
+pub(crate) struct Check {
+  pub(crate) name: String,
+  #[cfg_attr(
+    feature = "serde1",
+    serde(default, skip_serializing_if = "Option::is_none")
+  )]
+  pub(crate) path: Option<String>,
+  pub(crate) status: Severity,
+  #[cfg_attr(
+    feature = "serde1",
+    serde(default, skip_serializing_if = "Vec::is_empty")
+  )]
+  pub(crate) problems: Vec<String>,
+}
+
+impl Check {
+  fn new(name: String, path: Option<&Path>) -> Self {
+    Self {
+      name,
+      path: path.map(|path| path.to_string_lossy().into_owned()),
+      status: Severity::Pass,
+      problems: Vec::new(),
+    }
+  }
+
+  fn report<S: Into<String>>(&mut self, status: Severity, problem: S) {
+    self.status = self.status.max(status);
+    self.problems.push(problem.into());
+  }
+}
+
+/// An audit of the user's directory setup, for support requests.
+#[derive(Debug)]
+#[cfg_attr(feature = "serde1", derive(serde::Serialize, serde::Deserialize))]
+pub(crate) struct Diagnosis {
+  pub(crate) status: Severity,
+  pub(crate) checks: Vec<Check>,
+}
+
+impl Diagnosis {
+  /// Checks every base and user path, and every project path if project
+  /// options are given. Paths are only inspected on disk when resolving for
+  /// the host platform.
+  pub(crate) fn diagnose(resolver: &Resolver, project: Option<&ProjectOptions>) -> Self {
+    let host = resolver.platform == Platform::default();
+    let mut checks = vec![home(resolver, host)];
+
+    for path in Base::iter() {
+      let resolved = path.explain(resolver);
+      let permissions = path.permissions();
+      checks.extend(check(resolver, host, path.into(), resolved, permissions));
+    }
+
+    for path in User::iter() {
+      let resolved = path.explain(resolver);
+      let permissions = path.permissions();
+      checks.extend(check(resolver, host, path.into(), resolved, permissions));
+    }
+
+    if let Some(options) = project {
+      for path in Project::iter().filter(|path| *path != Project::PathFragment) {
+        let resolved = path.explain(resolver, options);
+        let permissions = path.permissions();
+        checks.extend(check(resolver, host, path.into(), resolved, permissions));
+      }
+    }
+
+    let status = checks
+      .iter()
+      .map(|check| check.status)
+      .max()
+      .unwrap_or(Severity::Pass);
+
+    Self { status, checks }
+  }
+
+  pub(crate) fn render(&self, format: &Format) -> Result<String> {
+    format.render(self, || {
+      let width = self
+        .checks
+        .iter()
+        .map(|check| check.name.len())
+        .max()
+        .unwrap_or_default();
+      let mut lines = Vec::new();
+      for check in &self.checks {
+        let path = check.path.as_deref().unwrap_or_default();
+        let line = format!("{:<4}  {:<width$}  {path}", check.status, check.name);
+        lines.push(line.trim_end().to_string());
+        lines.extend(
+          check
+            .problems
+            .iter()
+            .map(|problem| format!("      - {problem}")),
+        );
+      }
+      lines.push(format!("status: {}", self.status));
+      Ok(lines.join("\n"))
+    })
+  }
+}
+
+fn home(resolver: &Resolver, host: bool) -> Check {
+  let env = &resolver.env;
+  let mut check = Check::new("home".to_string(), Some(&env.home));
+
+  if resolver.platform == Platform::Linux && env.var("HOME").is_none() {
+    check.report(Severity::Warn, "HOME is not set");
+  }
+
+  if host {
+    if let Some(passwd) = account()
+      .map(|account| account.home)
+      .filter(|passwd| *passwd != env.home)
+    {
+      check.report(
+        Severity::Warn,
+        format!(
+          "home directory is {}, but the passwd entry says {}",
+          env.home.display(),
+          passwd.display()
+        ),
+      );
+    }
+  }
+
+  check
+}
+
+fn check(
+  resolver: &Resolver,
+  host: bool,
+  path: PlatformPath,
+  resolved: Result<Resolved>,
+  permissions: Permissions,
+) -> Option<Check> {
+  let status = path.status(&resolver.platform);
+  let name = format!("{} {path}", path.kind());
+
+  let resolved = match resolved {
+    Ok(resolved) => resolved,
+    Err(Error::RejectedInput(rejected)) => {
+      let mut check = Check::new(name, None);
+      check.report(Severity::Warn, format!("ignored {rejected}"));
+      return Some(check);
+    }
+    Err(_) if status == Status::Unsupported => return None,
+    Err(error) => {
+      let mut check = Check::new(name, None);
+      check.report(Severity::Warn, error.to_string());
+      return Some(check);
+    }
+  };
+
+  let mut check = Check::new(name, Some(&resolved.path));
+  for rejected in &resolved.rejected {
+    check.report(Severity::Warn, format!("ignored {rejected}"));
+  }
+
+  if resolved.path.to_str().is_none() {
+    check.report(Severity::Warn, "path is not valid UTF-8");
+  }
+
+  if host && resolved.path.is_absolute() {
+    inspect(&mut check, &resolved.path, permissions);
+  }
+
+  Some(check)
+}
+
+fn inspect(check: &mut Check, path: &Path, permissions: Permissions) {
+  if fs::symlink_metadata(path).is_err() {
+    check.report(Severity::Warn, "directory does not exist");
+    return;
+  }
+
+  match verify(path, permissions) {
+    Ok(()) => (),
+    Err(Error::UnsafeDirectory { reason, .. }) => check.report(Severity::Fail, reason),
+    Err(error) => check.report(Severity::Fail, error.to_string()),
+  }
+
+  if path.is_dir() && !writable(path) {
+    check.report(Severity::Fail, "directory is not writable");
+  }
+}
+
+#[cfg(unix)]
+fn writable(path: &Path) -> bool {
+  use std::ffi::CString;
+  use std::os::unix::ffi::OsStrExt;
+
+  match CString::new(path.as_os_str().as_bytes()) {
+    Ok(path) => unsafe { libc::access(path.as_ptr(), libc::W_OK) == 0 },
+    Err(_) => false,
+  }
+}
+
+#[cfg(not(unix))]
+fn writable(path: &Path) -> bool {
+  fs::metadata(path)
+    .map(|metadata| !metadata.permissions().readonly())
+    .unwrap_or(false)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::platform::Environment;
+
+  fn diagnose(env: Environment) -> Diagnosis {
+    Diagnosis::diagnose(&Resolver::new(Platform::Linux, env), None)
+  }
+
+  fn runtime(diagnosis: &Diagnosis) -> &Check {
+    diagnosis
+      .checks
+      .iter()
+      .find(|check| check.name == "base runtime")
+      .unwrap()
+  }
+
+  #[test]
+  fn a_relative_runtime_directory_is_reported() {
+    let env = Environment::new("/home/alice").with_var("XDG_RUNTIME_DIR", "run/user/1000");
+    let diagnosis = diagnose(env);
+    let check = runtime(&diagnosis);
+    assert_eq!(check.status, Severity::Warn);
+    assert_eq!(check.path, None);
+    assert_eq!(
+      check.problems,
+      ["ignored environment variable XDG_RUNTIME_DIR=run/user/1000 (not an absolute path)"]
+    );
+    assert!(diagnosis.status >= Severity::Warn);
+  }
+
+  #[test]
+  fn an_unset_runtime_directory_is_reported() {
+    let diagnosis = diagnose(Environment::new("/home/alice"));
+    let check = runtime(&diagnosis);
+    assert_eq!(check.status, Severity::Warn);
+    assert_eq!(
+      check.problems,
+      ["platform standard does not define requested directory"]
+    );
+  }
+}
@@ -0,0 +1,430 @@
+#[cfg(unix)]
+use super::account::{account, group, uid};
+use super::{Base, Platform, Project, ProjectOptions, Resolver, User};
+use crate::{Error, Result};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// The permissions expected of a directory, and how strictly to enforce them.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Permissions {
+  pub(crate) mode: u32,
+  pub(crate) exact: bool,
+}
+
+impl Permissions {
+  /// The XDG Base Directory Specification asks for 0700 when creating
+  /// directories, and requires exactly 0700 for the runtime directory.
+  const PRIVATE: Self = Self {
+    mode: 0o700,
+    exact: false,
+  };
+  const RUNTIME: Self = Self {
+    mode: 0o700,
+    exact: true,
+  };
+  const SHARED: Self = Self {
+    mode: 0o755,
+    exact: false,
+  };
+}
+
+impl Base {
+  pub(crate) fn permissions(&self) -> Permissions {
+    match self {
+      Self::Runtime => Permissions::RUNTIME,
+      Self::Home => Permissions::SHARED,
+      _ => Permissions::PRIVATE,
+    }
+  }
+
+  /// Creates the directory if it is missing, and verifies that it is safe to
+  /// use if it already exists.
+  pub fn ensure(&self, resolver: &Resolver) -> Result<PathBuf> {
+    ensure(resolver, self.resolve(resolver)?, self.permissions())
+  }
+}
+
+impl User {
+  pub(crate) fn permissions(&self) -> Permissions {
+    Permissions::SHARED
+  }
+
+  /// Creates the directory if it is missing, and verifies that it is safe to
+  /// use if it already exists.
+  pub fn ensure(&self, resolver: &Resolver) -> Result<PathBuf> {
+    ensure(resolver, self.resolve(resolver)?, self.permissions())
+  }
+}
+
+impl Project {
+  pub(crate) fn permissions(&self) -> Permissions {
+    match self {
+      Self::Runtime => Permissions::RUNTIME,
+      _ => Permissions::PRIVATE,
+    }
+  }
+
+  /// Creates the directory if it is missing, and verifies that it is safe to
+  /// use if it already exists.
+  pub fn ensure(&self, resolver: &Resolver, options: &ProjectOptions) -> Result<PathBuf> {
+    let path = self.resolve(resolver, options)?;
+    ensure(resolver, path, self.permissions())
+  }
+}
+
+fn ensure(resolver: &Resolver, path: PathBuf, permissions: Permissions) -> Result<PathBuf> {
+  if resolver.platform != Platform::default() {
+    return Err(Error::SimulatedPlatform(resolver.platform.clone()));
+  }
+
+  if !path.is_absolute() {
+    return Err(Error::UnsafeDirectory {
+      path,
+      reason: "path is not absolute".to_string(),
+    });
+  }
+
+  if fs::symlink_metadata(&path).is_err() {
+    create(&path, permissions)?;
+  }
+
+  verify(&path, permissions)?;
+
+  Ok(path)
+}
+
+#[cfg(unix)]
+fn create(path: &Path, permissions: Permissions) -> Result<()> {
+  use std::os::unix::fs::DirBuilderExt;
+
+  fs::DirBuilder::new()
+    .recursive(true)
+    .mode(permissions.mode)
+    .create(path)?;
+
+  // The mode given to DirBuilder is filtered through the umask.
+  set_mode(path, permissions.mode)
+}
+
+#[cfg(not(unix))]
+fn create(path: &Path, _permissions: Permissions) -> Result<()> {
+  Ok(fs::create_dir_all(path)?)
+}
+
+#[cfg(unix)]
+pub(crate) fn set_mode(path: &Path, mode: u32) -> Result<()> {
+  use std::os::unix::fs::PermissionsExt;
+
+  Ok(fs::set_permissions(path, fs::Permissions::from_mode(mode))?)
+}
+
+/// Creates a private directory in a shared directory such as `/tmp`, or
+/// verifies the one that is already there. Other users may have created the
+/// path first, or may try to replace it while it is checked, so the checks
+/// are made on the opened directory rather than on the path.
+#[cfg(unix)]
+pub(crate) fn ensure_private(path: &Path) -> Result<()> {
+  use std::io::ErrorKind;
+  use std::os::unix::fs::{DirBuilderExt, MetadataExt, OpenOptionsExt, PermissionsExt};
+
+  let unsafe_directory = |path: &Path, reason: &str| Error::UnsafeDirectory {
+    path: path.to_path_buf(),
+    reason: reason.to_string(),
+  };
+
+  // Anyone could rename the directory out of a parent that others can write
+  // to, unless the sticky bit restricts that to the directory's owner.
+  let parent = path
+    .parent()
+    .ok_or_else(|| unsafe_directory(path, "path has no parent directory"))?;
+  let mode = fs::metadata(parent)?.mode();
+  if mode & 0o002 != 0 && mode & 0o1000 == 0 {
+    return Err(unsafe_directory(
+      parent,
+      "writable by other users, without the sticky bit",
+    ));
+  }
+
+  let created = match fs::DirBuilder::new().mode(0o700).create(path) {
+    Ok(()) => true,
+    Err(error) if error.kind() == ErrorKind::AlreadyExists => false,
+    Err(error) => return Err(error.into()),
+  };
+
+  let directory = fs::OpenOptions::new()
+    .read(true)
+    .custom_flags(libc::O_DIRECTORY | libc::O_NOFOLLOW)
+    .open(path)
+    .map_err(|error| {
+      // Linux reports a symbolic link as ENOTDIR when O_DIRECTORY is given.
+      let symlink = fs::symlink_metadata(path)
+        .map(|metadata| metadata.file_type().is_symlink())
+        .unwrap_or(false);
+      match error.raw_os_error() {
+        Some(libc::ELOOP | libc::ENOTDIR) if symlink => {
+          unsafe_directory(path, "path is a symbolic link")
+        }
+        Some(libc::ENOTDIR) => unsafe_directory(path, "path is not a directory"),
+        _ => error.into(),
+      }
+    })?;
+
+  let metadata = directory.metadata()?;
+  let uid = uid();
+  if metadata.uid() != uid {
+    let owner = metadata.uid();
+    let reason = format!("owned by uid {owner}, not {uid}");
+    return Err(unsafe_directory(path, &reason));
+  }
+
+  // A directory that was just created only needs the umask undone. One that
+  // already existed with a looser mode may hold files that other users put
+  // there, so it is refused rather than corrected.
+  let mode = metadata.mode() & 0o7777;
+  match (mode, created) {
+    (0o700, _) => (),
+    (_, true) => directory.set_permissions(fs::Permissions::from_mode(0o700))?,
+    (mode, false) => {
+      let reason = format!("mode is {mode:o}, not 700");
+      return Err(unsafe_directory(path, &reason));
+    }
+  }
+
+  Ok(())
+}
+
+#[cfg(not(unix))]
+pub(crate) fn ensure_private(path: &Path) -> Result<()> {
+  fs::create_dir_all(path)?;
+  verify(path, Permissions::RUNTIME)
+}
+
+/// Checks that an existing directory is owned by the current user, and is not
+/// writable by anyone else.
+#[cfg(unix)]
+pub(crate) fn verify(path: &Path, permissions: Permissions) -> Result<()> {
+  use std::os::unix::fs::MetadataExt;
+
+  let unsafe_directory = |reason: String| Error::UnsafeDirectory {
+    path: path.to_path_buf(),
+    reason,
+  };
+
+  let metadata = match permissions.exact {
+    true => fs::symlink_metadata(path)?,
+    false => fs::metadata(path)?,
+  };
+
+  if metadata.file_type().is_symlink() {
+    return Err(unsafe_directory("path is a symbolic link".to_string()));
+  }
+
+  if !metadata.is_dir() {
+    return Err(unsafe_directory("path is not a directory".to_string()));
+  }
+
+  let uid = uid();
+  if metadata.uid() != uid {
+    let owner = metadata.uid();
+    return Err(unsafe_directory(format!("owned by uid {owner}, not {uid}")));
+  }
+
+  let mode = metadata.mode() & 0o7777;
+  let expected = permissions.mode;
+  if permissions.exact && mode != expected {
+    return Err(unsafe_directory(format!(
+      "mode is {mode:o}, not {expected:o}"
+    )));
+  }
+
+  if mode & 0o002 != 0 {
+    return Err(unsafe_directory(format!(
+      "mode is {mode:o}, which is writable by every user"
+    )));
+  }
+
+  // Distributions that give each user a private group default to umask 002,
+  // so directories writable by that group are only writable by the user.
+  let group = metadata.gid();
+  if mode & 0o020 != 0 && !private_group(group) {
+    return Err(unsafe_directory(format!(
+      "mode is {mode:o}, which is writable by group {group}, and that group is not private to the current user"
+    )));
+  }
+
+  Ok(())
+}
+
+/// Whether `gid` is the current user's private group: their primary group,
+/// named after them, with no other members.
+#[cfg(unix)]
+fn private_group(gid: u32) -> bool {
+  match (account(), group(gid)) {
+    (Some(account), Some(group)) => {
+      account.gid == gid
+        && group.name == account.name
+        && group.members.iter().all(|member| *member == account.name)
+    }
+    _ => false,
+  }
+}
+
+#[cfg(not(unix))]
+pub(crate) fn verify(path: &Path, _permissions: Permissions) -> Result<()> {
+  match fs::metadata(path)?.is_dir() {
+    true => Ok(()),
+    false => Err(Error::UnsafeDirectory {
+      path: path.to_path_buf(),
+      reason: "path is not a directory".to_string(),
+    }),
+  }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+  use super::*;
+  use crate::platform::testing::TempDir;
+  use crate::platform::{secure_temp, Environment, Resolved};
+  use std::fmt::Debug;
+  use std::os::unix::fs::{symlink, DirBuilderExt, MetadataExt};
+
+  fn reason<T: Debug>(result: Result<T>) -> String {
+    match result {
+      Err(Error::UnsafeDirectory { reason, .. }) => reason,
+      result => panic!("expected an unsafe directory, got {result:?}"),
+    }
+  }
+
+  #[test]
+  fn verify_accepts_private_directories() {
+    let directory = TempDir::new();
+    set_mode(directory.path(), 0o700).unwrap();
+    assert!(verify(directory.path(), Permissions::RUNTIME).is_ok());
+    assert!(verify(directory.path(), Permissions::PRIVATE).is_ok());
+  }
+
+  #[test]
+  fn verify_refuses_world_writable_directories() {
+    let directory = TempDir::new();
+    set_mode(directory.path(), 0o777).unwrap();
+    let reason = reason(verify(directory.path(), Permissions::PRIVATE));
+    assert_eq!(reason, "mode is 777, which is writable by every user");
+  }
+
+  #[test]
+  fn verify_requires_the_exact_runtime_mode() {
+    let directory = TempDir::new();
+    set_mode(directory.path(), 0o750).unwrap();
+    assert!(verify(directory.path(), Permissions::PRIVATE).is_ok());
+    let reason = reason(verify(directory.path(), Permissions::RUNTIME));
+    assert_eq!(reason, "mode is 750, not 700");
+  }
+
+  /// Resolves the secure temporary directory of the current user, with
+  /// `TMPDIR` set to `temp`.
+  fn secure_temp_in(temp: &Path) -> Result<Resolved> {
+    let env = Environment::new("/home/alice")
+      .with_var("TMPDIR", temp)
+      .with_uid(uid());
+    secure_temp(&Resolver::new(Platform::default(), env))
+  }
+
+  /// The name of the secure temporary directory of the current user.
+  fn runtime() -> String {
+    format!("runtime-{}", uid())
+  }
+
+  #[test]
+  fn secure_temp_creates_a_private_directory() {
+    let temp = TempDir::new();
+    let resolved = secure_temp_in(temp.path()).unwrap();
+    assert_eq!(resolved.path, temp.path().join(runtime()));
+    let metadata = fs::symlink_metadata(&resolved.path).unwrap();
+    assert!(metadata.is_dir());
+    assert_eq!(metadata.mode() & 0o7777, 0o700);
+
+    // The directory is reused once it exists.
+    assert_eq!(secure_temp_in(temp.path()).unwrap().path, resolved.path);
+  }
+
+  #[test]
+  fn secure_temp_is_named_after_the_uid_rather_than_the_user_name() {
+    let temp = TempDir::new();
+    let resolved = secure_temp_in(temp.path()).unwrap();
+    let env = Environment::new("/home/alice")
+      .with_var("TMPDIR", temp.path())
+      .with_var("USER", "alice")
+      .with_uid(uid());
+    let named = secure_temp(&Resolver::new(Platform::default(), env)).unwrap();
+    assert_eq!(named.path, resolved.path);
+  }
+
+  #[test]
+  fn secure_temp_is_not_defined_without_a_uid() {
+    let env = Environment::new("/Users/alice").with_var("USER", "alice");
+    let resolver = Resolver::new(Platform::MacOS, env);
+    assert!(matches!(
+      secure_temp(&resolver),
+      Err(Error::NotDefinedByPlatformStandard)
+    ));
+  }
+
+  #[test]
+  fn secure_temp_refuses_a_symbolic_link() {
+    let temp = TempDir::new();
+    let target = temp.path().join("elsewhere");
+    fs::DirBuilder::new().mode(0o700).create(&target).unwrap();
+    symlink(&target, temp.path().join(runtime())).unwrap();
+    let reason = reason(secure_temp_in(temp.path()));
+    assert_eq!(reason, "path is a symbolic link");
+  }
+
+  #[test]
+  fn secure_temp_refuses_a_directory_owned_by_another_user() {
+    use std::ffi::CString;
+    use std::os::unix::ffi::OsStrExt;
+
+    // Only root can give a directory away to another user.
+    if uid() != 0 {
+      return;
+    }
+    let temp = TempDir::new();
+    let path = temp.path().join(runtime());
+    fs::DirBuilder::new().mode(0o700).create(&path).unwrap();
+    let c_path = CString::new(path.as_os_str().as_bytes()).unwrap();
+    assert_eq!(unsafe { libc::chown(c_path.as_ptr(), 1, 1) }, 0);
+    let reason = reason(secure_temp_in(temp.path()));
+    assert_eq!(reason, "owned by uid 1, not 0");
+  }
+
+  #[test]
+  fn secure_temp_refuses_a_parent_without_the_sticky_bit() {
+    let temp = TempDir::new();
+    set_mode(temp.path(), 0o777).unwrap();
+    let reason = reason(secure_temp_in(temp.path()));
+    assert_eq!(reason, "writable by other users, without the sticky bit");
+
+    set_mode(temp.path(), 0o1777).unwrap();
+    assert!(secure_temp_in(temp.path()).is_ok());
+  }
+
+  #[test]
+  fn secure_temp_refuses_a_directory_with_loose_permissions() {
+    let temp = TempDir::new();
+    let path = temp.path().join(runtime());
+    fs::create_dir(&path).unwrap();
+    set_mode(&path, 0o777).unwrap();
+    let reason = reason(secure_temp_in(temp.path()));
+    assert_eq!(reason, "mode is 777, not 700");
+    assert_eq!(fs::metadata(&path).unwrap().mode() & 0o7777, 0o777);
+  }
+
+  #[test]
+  fn secure_temp_refuses_a_file() {
+    let temp = TempDir::new();
+    temp.write(&runtime(), "");
+    let reason = reason(secure_temp_in(temp.path()));
+    assert_eq!(reason, "path is not a directory");
+  }
+}
@@ -0,0 +1,318 @@
+use super::{Platform, Rejected, Resolved, Source};
+use crate::{Error, Result};
+use directories::BaseDirs;
+use std::collections::HashMap;
+use std::ffi::{OsStr, OsString};
+use std::path::{Path, PathBuf};
+use strum::Display;
+
+#[derive(Debug, Clone, Default)]
+pub struct Environment {
+  pub home: PathBuf,
+  pub vars: HashMap<String, OsString>,
+  /// The user id that per-user paths such as `secure-temp` are named after,
+  /// which is only known for the current user of the host.
+  pub uid: Option<u32>,
+}
+
+impl Environment {
+  pub fn new<P: Into<PathBuf>>(home: P) -> Self {
+    Self {
+      home: home.into(),
+      vars: HashMap::new(),
+      uid: None,
+    }
+  }
+
+  pub fn from_process() -> Result<Self> {
+    Ok(Self {
+      home: Self::host_home()?,
+      uid: Self::host_uid(),
+      ..Self::process_vars()
+    })
+  }
+
+  /// The variables of the current process, with no home directory.
+  pub(crate) fn process_vars() -> Self {
+    let vars = std::env::vars_os()
+      .filter_map(|(name, value)| Some((name.into_string().ok()?, value)))
+      .collect();
+
+    Self {
+      home: PathBuf::new(),
+      vars,
+      uid: None,
+    }
+  }
+
+  /// The home directory of the current user, according to the host.
+  pub(crate) fn host_home() -> Result<PathBuf> {
+    let home = BaseDirs::new()
+      .ok_or(Error::InvalidHomeDirectory)?
+      .home_dir()
+      .to_path_buf();
+
+    Ok(home)
+  }
+
+  /// The user id of the current user, according to the host.
+  #[cfg(unix)]
+  pub(crate) fn host_uid() -> Option<u32> {
+    Some(super::account::uid())
+  }
+
+  #[cfg(not(unix))]
+  pub(crate) fn host_uid() -> Option<u32> {
+    None
+  }
+
+  pub fn with_uid(mut self, uid: u32) -> Self {
+    self.uid = Some(uid);
+    self
+  }
+
+  pub fn with_var<K: Into<String>, V: Into<OsString>>(mut self, name: K, value: V) -> Self {
+    self.vars.insert(name.into(), value.into());
+    self
+  }
+
+  pub fn var(&self, name: &str) -> Option<&OsStr> {
+    self
+      .vars
+      .get(name)
+      .map(OsString::as_os_str)
+      .filter(|value| !value.is_empty())
+  }
+
+  /// The XDG specifications require these variables to hold absolute paths,
+  /// and relative values to be ignored.
+  pub(crate) fn absolute_var(&self, name: &str, rejected: &mut Vec<Rejected>) -> Option<Resolved> {
+    let value = self.var(name)?;
+    if !value.to_string_lossy().starts_with('/') {
+      rejected.push(Rejected::new(
+        Source::variable(name, value),
+        "not an absolute path",
+      ));
+      return None;
+    }
+    Some(Resolved::new(
+      PathBuf::from(value),
+      Source::variable(name, value),
+    ))
+  }
+
+  fn resolved_var(&self, name: &str) -> Option<Resolved> {
+    let value = self.var(name)?;
+    Some(Resolved::new(
+      PathBuf::from(value),
+      Source::variable(name, value),
+    ))
+  }
+
+  /// Expands a leading `~` to the home directory, and `$NAME` or `${NAME}`
+  /// to the value of a variable, as a shell would. Undefined variables are an
+  /// error, rather than expanding to nothing.
+  pub fn expand(&self, value: &str) -> Result<PathBuf> {
+    let mut expanded = OsString::new();
+    let rest = match value.strip_prefix('~') {
+      Some(rest) if rest.is_empty() || rest.starts_with(['/', '\\']) => {
+        expanded.push(&self.home);
+        rest
+      }
+      _ => value,
+    };
+
+    let mut chars = rest.char_indices().peekable();
+    let mut literal = 0;
+    while let Some((index, char)) = chars.next() {
+      if char != '$' {
+        continue;
+      }
+      let braced = chars.next_if(|(_, char)| *char == '{').is_some();
+      let start = chars.peek().map(|(index, _)| *index).unwrap_or(rest.len());
+      let mut end = start;
+      while let Some((index, _)) =
+        chars.next_if(|(_, char)| char.is_ascii_alphanumeric() || *char == '_')
+      {
+        end = index + 1;
+      }
+      if braced && chars.next_if(|(_, char)| *char == '}').is_none() {
+        return Err(Error::InvalidRequest(format!(
+          "unterminated variable in {value}"
+        )));
+      }
+      let name = &rest[start..end];
+      if name.is_empty() {
+        continue;
+      }
+      let value = self
+        .var(name)
+        .ok_or_else(|| Error::InvalidRequest(format!("undefined variable: {name}")))?;
+      expanded.push(&rest[literal..index]);
+      expanded.push(value);
+      literal = chars.peek().map(|(index, _)| *index).unwrap_or(rest.len());
+    }
+    expanded.push(&rest[literal..]);
+
+    Ok(PathBuf::from(expanded))
+  }
+
+  pub(crate) fn windows_home(&self) -> Resolved {
+    self
+      .resolved_var("USERPROFILE")
+      .unwrap_or_else(|| Resolved::new(self.home.clone(), Source::Home))
+  }
+
+  pub(crate) fn known_folder(&self, name: &str, default: &[&str]) -> Resolved {
+    self.resolved_var(name).unwrap_or_else(|| {
+      let path = Platform::Windows.join(&self.windows_home().path, default);
+      Resolved::new(path, Source::Default)
+    })
+  }
+
+  pub(crate) fn system_folder(&self, name: &str, default: &[&str]) -> Resolved {
+    let drive = self.var("SystemDrive").unwrap_or_else(|| OsStr::new("C:"));
+    self.resolved_var(name).unwrap_or_else(|| {
+      let path = Platform::Windows.join(Path::new(drive), default);
+      Resolved::new(path, Source::Default)
+    })
+  }
+}
+
+/// The Windows Known Folders that paths are derived from, named after their
+/// `FOLDERID`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Display)]
+pub(crate) enum KnownFolder {
+  Profile,
+  RoamingAppData,
+  LocalAppData,
+  Desktop,
+  Documents,
+  Downloads,
+  Music,
+  Pictures,
+  Public,
+  Templates,
+  Videos,
+}
+
+impl KnownFolder {
+  /// Asks Windows where the folder is, which accounts for folders that the
+  /// user or an administrator has redirected, such as to OneDrive.
+  #[cfg(windows)]
+  pub(crate) fn lookup(&self) -> Option<PathBuf> {
+    let base = BaseDirs::new()?;
+    let user = directories::UserDirs::new()?;
+    let path = match self {
+      Self::Profile => base.home_dir(),
+      Self::RoamingAppData => base.data_dir(),
+      Self::LocalAppData => base.data_local_dir(),
+      Self::Desktop => user.desktop_dir()?,
+      Self::Documents => user.document_dir()?,
+      Self::Downloads => user.download_dir()?,
+      Self::Music => user.audio_dir()?,
+      Self::Pictures => user.picture_dir()?,
+      Self::Public => user.public_dir()?,
+      Self::Templates => user.template_dir()?,
+      Self::Videos => user.video_dir()?,
+    };
+    Some(path.to_path_buf())
+  }
+
+  #[cfg(not(windows))]
+  pub(crate) fn lookup(&self) -> Option<PathBuf> {
+    None
+  }
+}
+
+impl Platform {
+  pub(crate) fn separator(&self) -> char {
+    match self {
+      Self::Windows => '\\',
+      _ => '/',
+    }
+  }
+
+  /// The separator used between paths in lists such as `PATH`.
+  pub(crate) fn list_separator(&self) -> char {
+    match self {
+      Self::Windows => ';',
+      _ => ':',
+    }
+  }
+
+  /// Joins path components using the separator of this platform, rather than
+  /// the separator of the host.
+  pub(crate) fn join<S: AsRef<OsStr>>(&self, base: &Path, components: &[S]) -> PathBuf {
+    let separator = self.separator();
+    let mut path = base.as_os_str().to_os_string();
+    for component in components {
+      if !path.is_empty() && !path.to_string_lossy().ends_with(separator) {
+        path.push(separator.to_string());
+      }
+      path.push(component);
+    }
+    PathBuf::from(path)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn env() -> Environment {
+    Environment::new("/home/alice").with_var("DATA", "/srv/data")
+  }
+
+  fn expand(value: &str) -> Result<PathBuf> {
+    env().expand(value)
+  }
+
+  #[test]
+  fn expands_the_home_directory() {
+    assert_eq!(expand("~").unwrap(), PathBuf::from("/home/alice"));
+    assert_eq!(
+      expand("~/notes").unwrap(),
+      PathBuf::from("/home/alice/notes")
+    );
+  }
+
+  #[test]
+  fn leaves_the_home_directory_of_other_users_alone() {
+    assert_eq!(expand("~bob/notes").unwrap(), PathBuf::from("~bob/notes"));
+  }
+
+  #[test]
+  fn expands_variables() {
+    assert_eq!(
+      expand("$DATA/cache").unwrap(),
+      PathBuf::from("/srv/data/cache")
+    );
+    assert_eq!(
+      expand("${DATA}cache").unwrap(),
+      PathBuf::from("/srv/datacache")
+    );
+  }
+
+  #[test]
+  fn keeps_dollar_signs_that_name_no_variable() {
+    assert_eq!(expand("/tmp/$$").unwrap(), PathBuf::from("/tmp/$$"));
+    assert_eq!(expand("/tmp/${}").unwrap(), PathBuf::from("/tmp/${}"));
+  }
+
+  #[test]
+  fn refuses_unterminated_variables() {
+    assert!(matches!(
+      expand("${DATA/cache"),
+      Err(Error::InvalidRequest(message)) if message.contains("unterminated")
+    ));
+  }
+
+  #[test]
+  fn refuses_undefined_variables() {
+    assert!(matches!(
+      expand("$MISSING/cache"),
+      Err(Error::InvalidRequest(message)) if message.contains("MISSING")
+    ));
+  }
+}
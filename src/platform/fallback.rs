@@ -0,0 +1,211 @@
+use super::ensure::ensure_private;
+use super::{Base, KnownFolder, Platform, Project, Resolved, Resolver, Source};
+use crate::{Error, Result};
+use std::fmt;
+use std::path::PathBuf;
+use std::str::FromStr;
+
+/// What to resolve instead of a path that the platform does not define.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum Fallback {
+  /// Report an error, as the platform standard does not define the path.
+  #[default]
+  Strict,
+  /// Use the paths that programs commonly use instead, such as `data-local`
+  /// for `state`, and `secure-temp` for `runtime`.
+  Conventional,
+  /// Try each of these paths in order, where `secure-temp` is a per-user
+  /// directory in the temporary directory.
+  Chain(Vec<String>),
+}
+
+impl Fallback {
+  /// The names of the paths to try instead of `path`, in order.
+  fn chain(&self, path: &str) -> Vec<String> {
+    match self {
+      Self::Strict => Vec::new(),
+      Self::Conventional => match path {
+        "state" => vec!["data-local".to_string()],
+        "runtime" => vec![SECURE_TEMP.to_string()],
+        _ => Vec::new(),
+      },
+      Self::Chain(names) => names.clone(),
+    }
+  }
+
+  /// Checks that every name in the chain is a path of kind `T`, so that a
+  /// chain that only another kind defines is refused even when the requested
+  /// path resolves without it.
+  pub(crate) fn validate<T: FromStr>(&self) -> Result<()> {
+    match self {
+      Self::Chain(names) => match names
+        .iter()
+        .find(|name| *name != SECURE_TEMP && name.parse::<T>().is_err())
+      {
+        Some(name) => Err(Error::InvalidPathName(name.clone())),
+        None => Ok(()),
+      },
+      Self::Strict | Self::Conventional => Ok(()),
+    }
+  }
+
+  /// Returns `resolved` if it succeeded, and otherwise the first fallback of
+  /// `path` that the platform defines. Other errors, such as unrecognized
+  /// names or unsafe directories, are reported rather than skipped.
+  pub(crate) fn apply<F>(
+    &self,
+    path: &str,
+    resolved: Result<Resolved>,
+    resolve: F,
+  ) -> Result<Resolved>
+  where
+    F: Fn(&str) -> Result<Resolved>,
+  {
+    let error = match resolved {
+      Ok(resolved) => return Ok(resolved),
+      Err(error) => error,
+    };
+    // An input that was rejected still explains why a fallback was used.
+    let rejected = match &error {
+      Error::RejectedInput(rejected) => vec![rejected.clone()],
+      _ => Vec::new(),
+    };
+
+    for name in self.chain(path) {
+      match resolve(&name) {
+        Ok(mut resolved) => {
+          resolved.fallback = Some(name);
+          return Ok(resolved.rejecting(rejected));
+        }
+        Err(Error::NotDefinedByPlatformStandard | Error::RejectedInput(_)) => continue,
+        Err(error) => return Err(error),
+      }
+    }
+
+    Err(error)
+  }
+}
+
+impl FromStr for Fallback {
+  type Err = Error;
+
+  fn from_str(value: &str) -> Result<Self> {
+    match value {
+      "strict" => Ok(Self::Strict),
+      "conventional" => Ok(Self::Conventional),
+      chain => {
+        let names: Vec<String> = chain
+          .split(',')
+          .map(str::trim)
+          .filter(|name| !name.is_empty())
+          .map(String::from)
+          .collect();
+        // The kind of path is not known yet, so a name is accepted here if
+        // either kind defines it, and checked against the kind it is used
+        // with by `validate`.
+        let known = |name: &String| {
+          name == SECURE_TEMP || name.parse::<Base>().is_ok() || name.parse::<Project>().is_ok()
+        };
+        match names.iter().find(|name| !known(name)) {
+          _ if names.is_empty() => Err(Error::InvalidPathName(value.to_string())),
+          Some(name) => Err(Error::InvalidPathName(name.clone())),
+          None => Ok(Self::Chain(names)),
+        }
+      }
+    }
+  }
+}
+
+impl fmt::Display for Fallback {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      Self::Strict => write!(f, "strict"),
+      Self::Conventional => write!(f, "conventional"),
+      Self::Chain(names) => write!(f, "{}", names.join(",")),
+    }
+  }
+}
+
+/// The name of the fallback for runtime files, for use in a chain.
+pub(crate) const SECURE_TEMP: &str = "secure-temp";
+
+/// A per-user directory in the temporary directory, named after the user's
+/// uid, for runtime files on platforms or sessions without a runtime
+/// directory. The uid is only known when resolving for the host, and when
+/// resolving for the
+/// host, the directory is created with mode 0700 if it is missing, and
+/// refused if it is a symbolic link, belongs to another user, or has any
+/// other mode. Windows already gives every user their own temporary
+/// directory.
+pub fn secure_temp(resolver: &Resolver) -> Result<Resolved> {
+  let Resolver { platform, env, .. } = resolver;
+  match platform {
+    Platform::Linux | Platform::MacOS => {
+      let temp = env
+        .absolute_var("TMPDIR", &mut Vec::new())
+        .unwrap_or_else(|| Resolved::new(PathBuf::from("/tmp"), Source::Default));
+      let uid = env.uid.ok_or(Error::NotDefinedByPlatformStandard)?;
+      let resolved = temp.join(platform, &[format!("runtime-{uid}")]);
+      if *platform == Platform::default() {
+        ensure_private(&resolved.path)?;
+      }
+      Ok(resolved)
+    }
+    Platform::Windows => Ok(
+      ["TEMP", "TMP"]
+        .iter()
+        .find_map(|name| {
+          let value = env.var(name)?;
+          Some(Resolved::new(
+            PathBuf::from(value),
+            Source::variable(name, value),
+          ))
+        })
+        .unwrap_or_else(|| {
+          resolver
+            .known_folder(KnownFolder::LocalAppData)
+            .join(platform, &["Temp"])
+        }),
+    ),
+    Platform::Wasm => Err(Error::NotDefinedByPlatformStandard),
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn parses_policies_and_chains() {
+    assert_eq!("strict".parse::<Fallback>().unwrap(), Fallback::Strict);
+    assert_eq!(
+      "conventional".parse::<Fallback>().unwrap(),
+      Fallback::Conventional
+    );
+    assert_eq!(
+      "data-local, secure-temp".parse::<Fallback>().unwrap(),
+      Fallback::Chain(vec!["data-local".to_string(), SECURE_TEMP.to_string()])
+    );
+  }
+
+  #[test]
+  fn rejects_unknown_names() {
+    for chain in ["nonsense", "data-local,nonsense", ","] {
+      assert!(
+        matches!(chain.parse::<Fallback>(), Err(Error::InvalidPathName(_))),
+        "{chain}"
+      );
+    }
+  }
+
+  #[test]
+  fn validates_names_against_the_kind_of_path() {
+    let chain: Fallback = "home,secure-temp".parse().unwrap();
+    assert!(chain.validate::<Base>().is_ok());
+    assert!(matches!(
+      chain.validate::<Project>(),
+      Err(Error::InvalidPathName(name)) if name == "home"
+    ));
+    assert!(Fallback::Conventional.validate::<Project>().is_ok());
+  }
+}
@@ -1,7 +1,7 @@
 use crate::output::Format;
-use crate::platform::path::{Base, Project, User};
+use crate::platform::path::Scope;
 use crate::platform::PlatformPathKind as Kind;
-use crate::platform::{Platform, PlatformPath, Status, StructuredStatus};
+use crate::platform::{Attribute, Platform, PlatformPath, Status, StructuredStatus};
 use std::collections::HashMap;
 use strum::IntoEnumIterator;
 
@@ -11,6 +11,8 @@ pub(crate) struct InfoFilter {
   pub(crate) platforms: Vec<Platform>,
   pub(crate) paths: Vec<Kind>,
   pub(crate) statuses: Vec<Status>,
+  /// Attributes that every path must have.
+  pub(crate) attributes: Vec<Attribute>,
 }
 
 #[derive(Debug)]
@@ -27,16 +29,21 @@ impl Info {
       for kind in Kind::iter().filter(|kind| filter.paths.contains(kind)) {
         let mut paths = HashMap::new();
 
-        let variant: Vec<PlatformPath> = match &kind {
-          Kind::Base => Base::iter().map(PlatformPath::from).collect(),
-          Kind::User => User::iter().map(PlatformPath::from).collect(),
-          Kind::Project => Project::iter().map(PlatformPath::from).collect(),
-        };
-
-        for path in variant {
+        for path in kind.paths() {
           let status = path.status(&platform);
-          if filter.statuses.contains(&status) {
-            let status = StructuredStatus { status };
+          let attributes = path.attributes(&platform);
+          let matched = filter
+            .attributes
+            .iter()
+            .all(|attribute| attributes.contains(attribute));
+          if filter.statuses.contains(&status) && matched {
+            let mut status = StructuredStatus::from(status);
+            status.attributes = attributes.into_iter().collect();
+            if let PlatformPath::Project(project) = &path {
+              status.scopes = Scope::iter()
+                .map(|scope| (scope, project.scoped_status(&platform, &scope)))
+                .collect();
+            }
             paths.insert(path, status);
           }
         }
@@ -51,28 +58,38 @@ impl Info {
   }
 
   pub(crate) fn render(&self, format: &Format) -> crate::Result<String> {
-    let rendered = match format {
-      #[cfg(feature = "json")]
-      Format::Json => serde_json::to_string(self)?,
-      #[cfg(feature = "json")]
-      Format::JsonPretty => serde_json::to_string_pretty(self)?,
-      #[cfg(feature = "yaml")]
-      Format::Yaml => serde_yaml::to_string(self)?,
-      Format::Text => {
-        let mut records = Vec::<String>::new();
-        for (platform, kinds) in self.0.iter() {
-          for (kind, paths) in kinds {
-            for (path, StructuredStatus { status }) in paths {
-              let path = path.to_string();
-              records.push(format!("{platform} {kind} {path} {status}"));
-            }
+    format.render(self, || {
+      let mut records = Vec::<String>::new();
+      for (platform, kinds) in self.0.iter() {
+        for (kind, paths) in kinds {
+          for (
+            path,
+            StructuredStatus {
+              status,
+              scopes,
+              attributes,
+            },
+          ) in paths
+          {
+            let path = path.to_string();
+            let scopes = scopes
+              .iter()
+              .map(|(scope, status)| format!(" {scope}={status}"))
+              .collect::<String>();
+            let attributes: Vec<String> =
+              attributes.set().iter().map(ToString::to_string).collect();
+            let attributes = match attributes.is_empty() {
+              true => String::new(),
+              false => format!(" attributes={}", attributes.join(",")),
+            };
+            records.push(format!(
+              "{platform} {kind} {path} {status}{scopes}{attributes}"
+            ));
           }
         }
-        records.sort();
-        records.join("\n")
       }
-    };
-
-    Ok(rendered)
+      records.sort();
+      Ok(records.join("\n"))
+    })
   }
 }
@@ -1,13 +1,25 @@
 mod base;
+mod integration;
 mod project;
+mod system;
+mod tooling;
 mod user;
 
-use super::{Platform, Status};
+use super::{Attribute, Platform, Status};
+use crate::output::Format;
 pub use base::*;
+pub use integration::*;
 pub use project::*;
+pub use system::*;
+pub use tooling::*;
 pub use user::*;
 
-use strum::{Display, EnumIter, EnumProperty, EnumString, EnumVariantNames};
+use super::{Rejected, Resolved, Resolver, Source};
+use crate::{Error, Result};
+use std::path::{Component, Path, PathBuf};
+use structopt::clap::App;
+use structopt::StructOpt;
+use strum::{Display, EnumIter, EnumProperty, EnumString, EnumVariantNames, IntoEnumIterator};
 
 #[derive(Debug, PartialEq, Eq, Hash)]
 #[cfg_attr(
@@ -19,26 +31,82 @@ pub(crate) enum PlatformPath {
   Base(Base),
   User(User),
   Project(Project),
+  System(System),
+  Integration(Integration),
+  Tooling(Tooling),
 }
 
 impl PlatformPath {
+  pub(crate) fn kind(&self) -> PlatformPathKind {
+    match self {
+      Self::Base(_) => PlatformPathKind::Base,
+      Self::User(_) => PlatformPathKind::User,
+      Self::Project(_) => PlatformPathKind::Project,
+      Self::System(_) => PlatformPathKind::System,
+      Self::Integration(_) => PlatformPathKind::Integration,
+      Self::Tooling(_) => PlatformPathKind::Tooling,
+    }
+  }
+
   pub(crate) fn status(&self, platform: &Platform) -> Status {
-    let prop = platform.into();
+    self.prop(platform.into()).into()
+  }
+
+  /// How the platform treats the directory, read from the
+  /// `<platform>_attributes` props.
+  pub(crate) fn attributes(&self, platform: &Platform) -> Vec<Attribute> {
+    let prop: &'static str = platform.into();
+    self
+      .prop(&format!("{prop}_attributes"))
+      .map(Attribute::parse_list)
+      .unwrap_or_default()
+  }
+
+  fn prop(&self, prop: &str) -> Option<&'static str> {
     match self {
       Self::Base(variant) => variant.get_str(prop),
       Self::User(variant) => variant.get_str(prop),
       Self::Project(variant) => variant.get_str(prop),
+      Self::System(variant) => variant.get_str(prop),
+      Self::Integration(variant) => variant.get_str(prop),
+      Self::Tooling(variant) => variant.get_str(prop),
     }
-    .into()
   }
 }
 
-impl ToString for PlatformPath {
-  fn to_string(&self) -> String {
+impl PlatformPath {
+  /// Resolves the path, as a list for kinds that name several directories.
+  /// Project paths require project options, and tooling paths use the
+  /// default tooling options.
+  pub(crate) fn resolve(
+    &self,
+    resolver: &Resolver,
+    project: Option<&ProjectOptions>,
+  ) -> Result<Vec<PathBuf>> {
+    let single = |path: Result<PathBuf>| path.map(|path| vec![path]);
     match self {
-      Self::Base(variant) => variant.to_string(),
-      Self::User(variant) => variant.to_string(),
-      Self::Project(variant) => variant.to_string(),
+      Self::Base(path) => single(path.resolve(resolver)),
+      Self::User(path) => single(path.resolve(resolver)),
+      Self::Project(path) => {
+        let options = project.ok_or(Error::NotDefinedByPlatformStandard)?;
+        single(path.resolve(resolver, options))
+      }
+      Self::System(path) => path.resolve(resolver, project),
+      Self::Integration(path) => single(path.resolve(resolver)),
+      Self::Tooling(path) => single(path.resolve(resolver, &ToolingOptions::default())),
+    }
+  }
+}
+
+impl std::fmt::Display for PlatformPath {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    match self {
+      Self::Base(variant) => variant.fmt(f),
+      Self::User(variant) => variant.fmt(f),
+      Self::Project(variant) => variant.fmt(f),
+      Self::System(variant) => variant.fmt(f),
+      Self::Integration(variant) => variant.fmt(f),
+      Self::Tooling(variant) => variant.fmt(f),
     }
   }
 }
@@ -61,6 +129,70 @@ impl From<Project> for PlatformPath {
   }
 }
 
+impl From<System> for PlatformPath {
+  fn from(path: System) -> Self {
+    Self::System(path)
+  }
+}
+
+impl From<Integration> for PlatformPath {
+  fn from(path: Integration) -> Self {
+    Self::Integration(path)
+  }
+}
+
+impl From<Tooling> for PlatformPath {
+  fn from(path: Tooling) -> Self {
+    Self::Tooling(path)
+  }
+}
+
+/// Combines a user directory with the corresponding system directories. The
+/// result is only an error if none of them are defined.
+fn search_paths(
+  path: Result<PathBuf>,
+  system: Option<System>,
+  resolver: &Resolver,
+  project: Option<&ProjectOptions>,
+) -> Result<Vec<PathBuf>> {
+  let system = system.and_then(|system| system.resolve(resolver, project).ok());
+  let paths: Vec<PathBuf> = path
+    .iter()
+    .cloned()
+    .chain(system.into_iter().flatten())
+    .collect();
+  match paths.is_empty() {
+    true => path.map(|path| vec![path]),
+    false => Ok(paths),
+  }
+}
+
+/// Looks for `name` in each of the given directories, in order. The name
+/// must be relative, and must not climb out of the directories with `..`.
+pub fn find<P: AsRef<Path>>(search_paths: &[PathBuf], name: P) -> Result<Vec<PathBuf>> {
+  let name = name.as_ref();
+  let escapes = name
+    .components()
+    .any(|component| !matches!(component, Component::Normal(_) | Component::CurDir));
+  if escapes || name.as_os_str().is_empty() {
+    return Err(Error::InvalidRequest(format!(
+      "file name must be a relative path inside the search directories: {}",
+      name.display()
+    )));
+  }
+
+  let found: Vec<PathBuf> = search_paths
+    .iter()
+    .map(|path| path.join(name))
+    .filter(|path| path.exists())
+    .collect();
+
+  match found.is_empty() {
+    true => Err(Error::NotFound),
+    false => Ok(found),
+  }
+}
+
 #[derive(Debug, PartialEq, Eq, Hash, Display, EnumString, EnumVariantNames, EnumIter)]
 #[strum(serialize_all = "lowercase")]
 #[cfg_attr(
@@ -72,16 +204,260 @@ pub(crate) enum PlatformPathKind {
   Base,
   User,
   Project,
+  System,
+  Integration,
+  Tooling,
+}
+
+impl PlatformPathKind {
+  /// Every path of this kind, in declaration order.
+  pub(crate) fn paths(&self) -> Vec<PlatformPath> {
+    match self {
+      Self::Base => Base::iter().map(PlatformPath::from).collect(),
+      Self::User => User::iter().map(PlatformPath::from).collect(),
+      Self::Project => Project::iter().map(PlatformPath::from).collect(),
+      Self::System => System::iter().map(PlatformPath::from).collect(),
+      Self::Integration => Integration::iter().map(PlatformPath::from).collect(),
+      Self::Tooling => Tooling::iter().map(PlatformPath::from).collect(),
+    }
+  }
+
+  /// The command-line definition of this kind, which holds the descriptions
+  /// of its paths.
+  pub(crate) fn clap(&self) -> App<'static, 'static> {
+    match self {
+      Self::Base => Base::clap(),
+      Self::User => User::clap(),
+      Self::Project => Project::clap(),
+      Self::System => System::clap(),
+      Self::Integration => Integration::clap(),
+      Self::Tooling => Tooling::clap(),
+    }
+  }
 }
 
 #[derive(Debug)]
 #[cfg_attr(feature = "serde1", derive(serde::Serialize, serde::Deserialize))]
 pub(crate) struct StructuredPathString {
   pub(crate) path: String,
+  #[cfg_attr(
+    feature = "serde1",
+    serde(default, skip_serializing_if = "Option::is_none")
+  )]
+  pub(crate) sandbox: Option<String>,
+  #[cfg_attr(
+    feature = "serde1",
+    serde(default, skip_serializing_if = "Option::is_none")
+  )]
+  pub(crate) source: Option<Source>,
+  #[cfg_attr(
+    feature = "serde1",
+    serde(default, skip_serializing_if = "Vec::is_empty")
+  )]
+  pub(crate) rejected: Vec<Rejected>,
+  /// The fallback that was used, if the requested path is not defined.
+  #[cfg_attr(
+    feature = "serde1",
+    serde(default, skip_serializing_if = "Option::is_none")
+  )]
+  pub(crate) fallback: Option<String>,
 }
 
 impl From<String> for StructuredPathString {
   fn from(path: String) -> Self {
-    Self { path }
+    Self {
+      path,
+      sandbox: None,
+      source: None,
+      rejected: Vec::new(),
+      fallback: None,
+    }
+  }
+}
+
+#[derive(Debug)]
+#[cfg_attr(feature = "serde1", derive(serde::Serialize, serde::Deserialize))]
+pub(crate) struct StructuredPathList {
+  pub(crate) paths: Vec<String>,
+  #[cfg_attr(
+    feature = "serde1",
+    serde(default, skip_serializing_if = "Option::is_none")
+  )]
+  pub(crate) sandbox: Option<String>,
+  #[cfg_attr(
+    feature = "serde1",
+    serde(default, skip_serializing_if = "Option::is_none")
+  )]
+  pub(crate) source: Option<Source>,
+  #[cfg_attr(
+    feature = "serde1",
+    serde(default, skip_serializing_if = "Vec::is_empty")
+  )]
+  pub(crate) rejected: Vec<Rejected>,
+  /// The fallback that was used, if the requested path is not defined.
+  #[cfg_attr(
+    feature = "serde1",
+    serde(default, skip_serializing_if = "Option::is_none")
+  )]
+  pub(crate) fallback: Option<String>,
+}
+
+impl From<Vec<String>> for StructuredPathList {
+  fn from(paths: Vec<String>) -> Self {
+    Self {
+      paths,
+      sandbox: None,
+      source: None,
+      rejected: Vec::new(),
+      fallback: None,
+    }
+  }
+}
+
+#[derive(Debug)]
+#[cfg_attr(feature = "serde1", derive(serde::Serialize, serde::Deserialize))]
+pub(crate) struct StructuredError {
+  pub(crate) error: String,
+}
+
+#[derive(Debug)]
+#[cfg_attr(
+  feature = "serde1",
+  derive(serde::Serialize, serde::Deserialize),
+  serde(untagged)
+)]
+pub(crate) enum StructuredPath {
+  Path(StructuredPathString),
+  Paths(StructuredPathList),
+  Error(StructuredError),
+}
+
+impl From<String> for StructuredPath {
+  fn from(path: String) -> Self {
+    Self::Path(path.into())
+  }
+}
+
+impl From<Vec<String>> for StructuredPath {
+  fn from(paths: Vec<String>) -> Self {
+    Self::Paths(paths.into())
+  }
+}
+
+impl From<Error> for StructuredPath {
+  fn from(error: Error) -> Self {
+    let error = error.to_string();
+    Self::Error(StructuredError { error })
+  }
+}
+
+impl StructuredPath {
+  /// Reports the sandbox that the paths were resolved for, if any.
+  pub(crate) fn sandboxed(mut self, resolver: &Resolver) -> Self {
+    let sandbox = resolver.sandbox.as_ref().map(ToString::to_string);
+    match &mut self {
+      Self::Path(path) => path.sandbox = sandbox,
+      Self::Paths(paths) => paths.sandbox = sandbox,
+      Self::Error(_) => (),
+    }
+    self
+  }
+
+  /// Reports the input that decided the paths, and any inputs that were
+  /// rejected along the way.
+  pub(crate) fn explained<T>(mut self, resolved: Resolved<T>) -> Self {
+    let Resolved {
+      source,
+      rejected,
+      fallback,
+      ..
+    } = resolved;
+    match &mut self {
+      Self::Path(path) => {
+        (path.source, path.rejected, path.fallback) = (Some(source), rejected, fallback)
+      }
+      Self::Paths(paths) => {
+        (paths.source, paths.rejected, paths.fallback) = (Some(source), rejected, fallback)
+      }
+      Self::Error(_) => (),
+    }
+    self
+  }
+
+  pub(crate) fn render(&self, format: &Format) -> crate::Result<String> {
+    format.render(self, || {
+      let (mut lines, source, rejected) = match self {
+        Self::Path(StructuredPathString {
+          path,
+          source,
+          rejected,
+          ..
+        }) => (vec![path.clone()], source, rejected.as_slice()),
+        Self::Paths(StructuredPathList {
+          paths,
+          source,
+          rejected,
+          ..
+        }) => (paths.clone(), source, rejected.as_slice()),
+        Self::Error(StructuredError { error }) => return Ok(format!("error: {error}")),
+      };
+      lines.extend(source.iter().map(|source| format!("source: {source}")));
+      lines.extend(
+        rejected
+          .iter()
+          .map(|rejected| format!("rejected: {rejected}")),
+      );
+      Ok(lines.join("\n"))
+    })
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::platform::testing::TempDir;
+
+  #[test]
+  fn every_supported_path_has_attributes() {
+    for platform in Platform::iter().filter(|platform| *platform != Platform::Wasm) {
+      for path in PlatformPathKind::iter().flat_map(|kind| kind.paths()) {
+        if path.status(&platform) != Status::Supported {
+          continue;
+        }
+        let prop: &'static str = (&platform).into();
+        let prop = format!("{prop}_attributes");
+        assert!(path.prop(&prop).is_some(), "{path} has no {prop}");
+        path.attributes(&platform);
+      }
+    }
+  }
+
+  #[test]
+  fn find_returns_matches_in_order() {
+    let (user, system) = (TempDir::new(), TempDir::new());
+    system.write("nifty/settings.toml", "");
+    let user_file = user.write("nifty/settings.toml", "");
+    let search_paths = vec![user.path().to_path_buf(), system.path().to_path_buf()];
+
+    let found = find(&search_paths, "nifty/settings.toml").unwrap();
+    assert_eq!(found[0], user_file);
+    assert_eq!(found.len(), 2);
+    assert!(matches!(
+      find(&search_paths, "missing.toml"),
+      Err(Error::NotFound)
+    ));
+  }
+
+  #[test]
+  fn find_rejects_names_outside_the_search_paths() {
+    let directory = TempDir::new();
+    let search_paths = vec![directory.path().join("nifty")];
+    directory.write("secret", "");
+    for name in ["../secret", "/etc/passwd", "nifty/../../secret", ""] {
+      assert!(
+        matches!(find(&search_paths, name), Err(Error::InvalidRequest(_))),
+        "{name}"
+      );
+    }
   }
 }
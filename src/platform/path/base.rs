@@ -1,11 +1,18 @@
+use crate::platform::{
+  secure_temp, Fallback, KnownFolder, Platform, Project, Resolved, Resolver, Source, System,
+  SECURE_TEMP,
+};
 use crate::{Error, Result};
 use camino::Utf8PathBuf;
 use directories::BaseDirs;
 use std::path::PathBuf;
+use std::str::FromStr;
 use structopt::StructOpt;
 use strum::{Display, EnumIter, EnumProperty, EnumString};
 
-#[derive(Debug, PartialEq, Eq, Hash, Display, StructOpt, EnumString, EnumIter, EnumProperty)]
+#[derive(
+  Debug, Clone, Copy, PartialEq, Eq, Hash, Display, StructOpt, EnumString, EnumIter, EnumProperty,
+)]
 #[cfg_attr(
   feature = "serde1",
   derive(serde::Serialize, serde::Deserialize),
@@ -18,32 +25,108 @@ use strum::{Display, EnumIter, EnumProperty, EnumString};
 )]
 pub enum Base {
   #[structopt(about = "the user's cache directory")]
-  #[strum(props(linux = "supported", macos = "supported", windows = "supported"))]
+  #[strum(props(
+    linux = "supported",
+    macos = "supported",
+    windows = "supported",
+    linux_attributes = "sandbox-redirected",
+    macos_attributes = "os-may-purge,sandbox-redirected",
+    windows_attributes = "sandbox-redirected"
+  ))]
   Cache,
   #[structopt(about = "the user's config directory")]
-  #[strum(props(linux = "supported", macos = "supported", windows = "supported"))]
+  #[strum(props(
+    linux = "supported",
+    macos = "supported",
+    windows = "supported",
+    linux_attributes = "backed-up,sandbox-redirected",
+    macos_attributes = "backed-up,sandbox-redirected",
+    windows_attributes = "roams,sandbox-redirected"
+  ))]
   Config,
   #[structopt(about = "the user's data directory")]
-  #[strum(props(linux = "supported", macos = "supported", windows = "supported"))]
+  #[strum(props(
+    linux = "supported",
+    macos = "supported",
+    windows = "supported",
+    linux_attributes = "backed-up,sandbox-redirected",
+    macos_attributes = "backed-up,sandbox-redirected",
+    windows_attributes = "roams,sandbox-redirected"
+  ))]
   Data,
   #[structopt(about = "the user's local data directory")]
-  #[strum(props(linux = "supported", macos = "supported", windows = "supported"))]
+  #[strum(props(
+    linux = "supported",
+    macos = "supported",
+    windows = "supported",
+    linux_attributes = "backed-up,sandbox-redirected",
+    macos_attributes = "backed-up,sandbox-redirected",
+    windows_attributes = "sandbox-redirected"
+  ))]
   DataLocal,
   #[structopt(about = "the user's executable directory")]
-  #[strum(props(linux = "supported", macos = "unsupported", windows = "unsupported"))]
+  #[strum(props(
+    linux = "supported",
+    macos = "unsupported",
+    windows = "unsupported",
+    linux_attributes = "backed-up,sandbox-redirected"
+  ))]
   Executable,
   #[structopt(about = "the user's home directory")]
-  #[strum(props(linux = "supported", macos = "supported", windows = "supported"))]
+  #[strum(props(
+    linux = "supported",
+    macos = "supported",
+    windows = "supported",
+    linux_attributes = "backed-up,user-visible,sandbox-redirected",
+    macos_attributes = "backed-up,user-visible,sandbox-redirected",
+    windows_attributes = "backed-up,user-visible"
+  ))]
   Home,
+  #[structopt(about = "the user's log directory")]
+  #[strum(props(
+    linux = "supported",
+    macos = "supported",
+    windows = "supported",
+    linux_attributes = "sandbox-redirected",
+    macos_attributes = "sandbox-redirected",
+    windows_attributes = "sandbox-redirected"
+  ))]
+  Log,
   #[structopt(about = "the user's preference directory")]
-  #[strum(props(linux = "supported", macos = "supported", windows = "supported"))]
+  #[strum(props(
+    linux = "supported",
+    macos = "supported",
+    windows = "supported",
+    linux_attributes = "backed-up,sandbox-redirected",
+    macos_attributes = "backed-up,sandbox-redirected",
+    windows_attributes = "roams,sandbox-redirected"
+  ))]
   Preference,
   #[structopt(about = "the user's runtime directory")]
-  #[strum(props(linux = "supported", macos = "unsupported", windows = "unsupported"))]
+  #[strum(props(
+    linux = "supported",
+    macos = "unsupported",
+    windows = "unsupported",
+    linux_attributes = "os-may-purge"
+  ))]
   Runtime,
   #[structopt(about = "the user's state directory")]
-  #[strum(props(linux = "supported", macos = "unsupported", windows = "unsupported"))]
+  #[strum(props(
+    linux = "supported",
+    macos = "unsupported",
+    windows = "unsupported",
+    linux_attributes = "sandbox-redirected"
+  ))]
   State,
+  #[structopt(about = "the user's trash directory")]
+  #[strum(props(
+    linux = "supported",
+    macos = "supported",
+    windows = "unsupported",
+    linux_attributes = "os-may-purge,user-visible,sandbox-redirected",
+    macos_attributes = "os-may-purge,user-visible"
+  ))]
+  Trash,
 }
 
 impl Base {
@@ -51,27 +134,253 @@ impl Base {
     BaseDirs::new().ok_or(Error::InvalidHomeDirectory)
   }
 
-  pub(crate) fn path_buf(&self) -> Result<PathBuf> {
-    Self::dirs().and_then(|base| {
-      match self {
-        Self::Cache => Some(base.cache_dir()),
-        Self::Config => Some(base.config_dir()),
-        Self::Data => Some(base.data_dir()),
-        Self::DataLocal => Some(base.data_local_dir()),
-        Self::Executable => base.executable_dir(),
-        Self::Home => Some(base.home_dir()),
-        Self::Preference => Some(base.preference_dir()),
-        Self::Runtime => base.runtime_dir(),
-        Self::State => base.state_dir(),
-      }
-      .ok_or(Error::NotDefinedByPlatformStandard)
-      .map(|path| path.to_path_buf())
-    })
+  /// Resolves the path for the current user on this machine, as
+  /// `resolve` does with `Resolver::from_process`.
+  pub fn utf8_path_buf(&self) -> Result<Utf8PathBuf> {
+    let path = self.resolve(&Resolver::from_process()?)?;
+    Ok(Utf8PathBuf::try_from(path)?)
   }
 
-  pub fn utf8_path_buf(&self) -> Result<Utf8PathBuf> {
-    self
-      .path_buf()
-      .and_then(|path| Ok(Utf8PathBuf::try_from(path)?))
+  pub fn resolve(&self, resolver: &Resolver) -> Result<PathBuf> {
+    self.explain(resolver).map(|resolved| resolved.path)
+  }
+
+  /// Resolves the path along with the inputs that decided it.
+  pub fn explain(&self, resolver: &Resolver) -> Result<Resolved> {
+    let Resolver { platform, env, .. } = resolver;
+    let default =
+      |components: &[&str]| Resolved::new(platform.join(&env.home, components), Source::Default);
+    match platform {
+      Platform::Linux => match self {
+        Self::Cache => Some(resolver.xdg_home("XDG_CACHE_HOME", &[".cache"])),
+        Self::Config | Self::Preference => Some(resolver.xdg_home("XDG_CONFIG_HOME", &[".config"])),
+        Self::Data | Self::DataLocal => {
+          Some(resolver.xdg_home("XDG_DATA_HOME", &[".local", "share"]))
+        }
+        Self::Executable => Some(resolver.xdg_home("XDG_BIN_HOME", &[".local", "bin"])),
+        Self::Home => Some(resolver.home()),
+        // The XDG Base Directory Specification lists logs as state.
+        Self::Log | Self::State => Some(resolver.xdg_home("XDG_STATE_HOME", &[".local", "state"])),
+        Self::Runtime => {
+          let mut rejected = Vec::new();
+          match env.absolute_var("XDG_RUNTIME_DIR", &mut rejected) {
+            Some(resolved) => Some(resolved),
+            None => match rejected.pop() {
+              Some(rejected) => return Err(Error::RejectedInput(rejected)),
+              None => None,
+            },
+          }
+        }
+        Self::Trash => Some(
+          resolver
+            .xdg_home("XDG_DATA_HOME", &[".local", "share"])
+            .join(platform, &["Trash"]),
+        ),
+      },
+      Platform::MacOS => match self {
+        Self::Cache => Some(default(&["Library", "Caches"])),
+        Self::Config | Self::Data | Self::DataLocal => {
+          Some(default(&["Library", "Application Support"]))
+        }
+        Self::Home => Some(Resolved::new(env.home.clone(), Source::Home)),
+        Self::Log => Some(default(&["Library", "Logs"])),
+        Self::Preference => Some(default(&["Library", "Preferences"])),
+        Self::Trash => Some(default(&[".Trash"])),
+        Self::Executable | Self::Runtime | Self::State => None,
+      },
+      Platform::Windows => match self {
+        Self::Cache | Self::DataLocal | Self::Log => {
+          Some(resolver.known_folder(KnownFolder::LocalAppData))
+        }
+        Self::Config | Self::Data | Self::Preference => {
+          Some(resolver.known_folder(KnownFolder::RoamingAppData))
+        }
+        Self::Home => Some(resolver.known_folder(KnownFolder::Profile)),
+        Self::Executable | Self::Runtime | Self::State | Self::Trash => None,
+      },
+      Platform::Wasm => None,
+    }
+    .ok_or(Error::NotDefinedByPlatformStandard)
+  }
+
+  /// The project directory that is kept inside this directory, if any.
+  pub fn project(&self) -> Option<Project> {
+    match self {
+      Self::Cache => Some(Project::Cache),
+      Self::Config => Some(Project::Config),
+      Self::Data => Some(Project::Data),
+      Self::DataLocal => Some(Project::DataLocal),
+      Self::Log => Some(Project::Log),
+      Self::Preference => Some(Project::Preference),
+      Self::Runtime => Some(Project::Runtime),
+      Self::State => Some(Project::State),
+      Self::Executable | Self::Home | Self::Trash => None,
+    }
+  }
+
+  /// Resolves the path, or the first fallback that the platform defines.
+  pub fn explain_with(&self, resolver: &Resolver, fallback: &Fallback) -> Result<Resolved> {
+    fallback.validate::<Self>()?;
+    fallback.apply(
+      &self.to_string(),
+      self.explain(resolver),
+      |name| match name {
+        SECURE_TEMP => secure_temp(resolver),
+        name => Self::from_str(name)
+          .map_err(|_| Error::InvalidPathName(name.to_string()))?
+          .explain(resolver),
+      },
+    )
+  }
+
+  /// The directories to search for existing files, in order of precedence.
+  pub fn search_paths(&self, resolver: &Resolver) -> Result<Vec<PathBuf>> {
+    let system = match self {
+      Self::Config | Self::Preference => Some(System::Config),
+      Self::Data | Self::DataLocal => Some(System::Data),
+      _ => None,
+    };
+    super::search_paths(self.resolve(resolver), system, resolver, None)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::platform::{Environment, Rejected};
+  use strum::IntoEnumIterator;
+
+  fn resolver(platform: Platform, env: Environment) -> Resolver {
+    Resolver::new(platform, env)
+  }
+
+  #[test]
+  fn linux_defaults_to_the_home_directory() {
+    let resolver = resolver(Platform::Linux, Environment::new("/home/alice"));
+    let resolved = Base::Cache.explain(&resolver).unwrap();
+    assert_eq!(resolved.path, PathBuf::from("/home/alice/.cache"));
+    assert_eq!(resolved.source, Source::Default);
+    assert_eq!(
+      Base::Log.resolve(&resolver).unwrap(),
+      PathBuf::from("/home/alice/.local/state")
+    );
+  }
+
+  #[test]
+  fn linux_reads_xdg_variables() {
+    let env = Environment::new("/home/alice").with_var("XDG_CONFIG_HOME", "/etc/alice");
+    let resolved = Base::Config
+      .explain(&resolver(Platform::Linux, env))
+      .unwrap();
+    assert_eq!(resolved.path, PathBuf::from("/etc/alice"));
+    assert_eq!(
+      resolved.source,
+      Source::variable("XDG_CONFIG_HOME", "/etc/alice".as_ref())
+    );
+  }
+
+  #[test]
+  fn linux_rejects_relative_xdg_variables() {
+    let env = Environment::new("/home/alice").with_var("XDG_DATA_HOME", "share");
+    let resolved = Base::Data.explain(&resolver(Platform::Linux, env)).unwrap();
+    assert_eq!(resolved.path, PathBuf::from("/home/alice/.local/share"));
+    assert_eq!(
+      resolved.rejected,
+      vec![Rejected::new(
+        Source::variable("XDG_DATA_HOME", "share".as_ref()),
+        "not an absolute path"
+      )]
+    );
+  }
+
+  #[test]
+  fn linux_runtime_requires_a_variable() {
+    let env = Environment::new("/home/alice");
+    assert!(matches!(
+      Base::Runtime.explain(&resolver(Platform::Linux, env.clone())),
+      Err(Error::NotDefinedByPlatformStandard)
+    ));
+
+    let env = env.with_var("XDG_RUNTIME_DIR", "/run/user/1000");
+    assert_eq!(
+      Base::Runtime
+        .resolve(&resolver(Platform::Linux, env))
+        .unwrap(),
+      PathBuf::from("/run/user/1000")
+    );
+  }
+
+  #[test]
+  fn linux_runtime_reports_a_relative_variable() {
+    let env = Environment::new("/home/alice").with_var("XDG_RUNTIME_DIR", "run/user/1000");
+    let rejected = Rejected::new(
+      Source::variable("XDG_RUNTIME_DIR", "run/user/1000".as_ref()),
+      "not an absolute path",
+    );
+    let resolver = resolver(Platform::Linux, env);
+    match Base::Runtime.explain(&resolver) {
+      Err(Error::RejectedInput(error)) => assert_eq!(error, rejected),
+      result => panic!("expected a rejected input, got {result:?}"),
+    }
+
+    let resolved = Base::Runtime
+      .explain_with(&resolver, &"data-local".parse().unwrap())
+      .unwrap();
+    assert_eq!(resolved.path, PathBuf::from("/home/alice/.local/share"));
+    assert_eq!(resolved.rejected, vec![rejected]);
+  }
+
+  #[test]
+  fn macos_uses_the_library() {
+    let resolver = resolver(Platform::MacOS, Environment::new("/Users/alice"));
+    assert_eq!(
+      Base::Config.resolve(&resolver).unwrap(),
+      PathBuf::from("/Users/alice/Library/Application Support")
+    );
+    assert_eq!(
+      Base::Log.resolve(&resolver).unwrap(),
+      PathBuf::from("/Users/alice/Library/Logs")
+    );
+    assert!(matches!(
+      Base::State.resolve(&resolver),
+      Err(Error::NotDefinedByPlatformStandard)
+    ));
+  }
+
+  #[test]
+  fn windows_reads_known_folder_variables() {
+    let env = Environment::new("/home/alice")
+      .with_var("USERPROFILE", r"C:\Users\alice")
+      .with_var("LOCALAPPDATA", r"D:\Local");
+    let resolver = resolver(Platform::Windows, env);
+    assert_eq!(
+      Base::Cache.resolve(&resolver).unwrap(),
+      PathBuf::from(r"D:\Local")
+    );
+    assert_eq!(
+      Base::Config.resolve(&resolver).unwrap(),
+      PathBuf::from(r"C:\Users\alice\AppData\Roaming")
+    );
+    assert!(matches!(
+      Base::Runtime.resolve(&resolver),
+      Err(Error::NotDefinedByPlatformStandard)
+    ));
+  }
+
+  #[test]
+  fn wasm_defines_nothing() {
+    let resolver = resolver(Platform::Wasm, Environment::new("/"));
+    assert!(Base::iter().all(|path| path.resolve(&resolver).is_err()));
+  }
+
+  #[test]
+  fn utf8_path_buf_agrees_with_resolve() {
+    let resolver = Resolver::from_process().unwrap();
+    for path in [Base::Cache, Base::Log] {
+      assert_eq!(
+        path.utf8_path_buf().unwrap().into_std_path_buf(),
+        path.resolve(&resolver).unwrap()
+      );
+    }
   }
 }
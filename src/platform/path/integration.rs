@@ -0,0 +1,109 @@
+use crate::platform::{Base, Platform, Resolved, Resolver, Source};
+use crate::{Error, Result};
+use std::path::PathBuf;
+use structopt::StructOpt;
+use strum::{Display, EnumIter, EnumProperty, EnumString};
+
+#[derive(Debug, PartialEq, Eq, Hash, Display, StructOpt, EnumString, EnumIter, EnumProperty)]
+#[cfg_attr(
+  feature = "serde1",
+  derive(serde::Serialize, serde::Deserialize),
+  serde(rename_all = "kebab-case")
+)]
+#[strum(serialize_all = "kebab-case")]
+#[structopt(
+  rename_all = "kebab-case",
+  about = "directories for integrating applications with the desktop"
+)]
+pub enum Integration {
+  #[structopt(about = "the user's application launcher directory")]
+  #[strum(props(
+    linux = "supported",
+    macos = "supported",
+    windows = "supported",
+    linux_attributes = "backed-up,sandbox-redirected",
+    macos_attributes = "backed-up,user-visible",
+    windows_attributes = "roams,sandbox-redirected"
+  ))]
+  Application,
+  #[structopt(about = "the user's directory of applications started at login")]
+  #[strum(props(
+    linux = "supported",
+    macos = "supported",
+    windows = "supported",
+    linux_attributes = "backed-up,sandbox-redirected",
+    macos_attributes = "backed-up",
+    windows_attributes = "roams,sandbox-redirected"
+  ))]
+  Autostart,
+  #[structopt(about = "the user's icon theme directory")]
+  #[strum(props(
+    linux = "supported",
+    macos = "unsupported",
+    windows = "unsupported",
+    linux_attributes = "backed-up,sandbox-redirected"
+  ))]
+  Icon,
+  #[structopt(about = "the user's MIME database directory")]
+  #[strum(props(
+    linux = "supported",
+    macos = "unsupported",
+    windows = "unsupported",
+    linux_attributes = "backed-up,sandbox-redirected"
+  ))]
+  Mime,
+  #[structopt(about = "the user's systemd unit directory")]
+  #[strum(props(
+    linux = "supported",
+    macos = "unsupported",
+    windows = "unsupported",
+    linux_attributes = "backed-up,sandbox-redirected"
+  ))]
+  SystemdUnit,
+}
+
+impl Integration {
+  pub fn resolve(&self, resolver: &Resolver) -> Result<PathBuf> {
+    self.explain(resolver).map(|resolved| resolved.path)
+  }
+
+  /// Resolves the path along with the inputs that decided it.
+  pub fn explain(&self, resolver: &Resolver) -> Result<Resolved> {
+    let Resolver { platform, env, .. } = resolver;
+    let join = |base: Base, components: &[&str]| -> Result<Resolved> {
+      Ok(base.explain(resolver)?.join(platform, components))
+    };
+    let resolved = match platform {
+      Platform::Linux => match self {
+        Self::Application => join(Base::Data, &["applications"])?,
+        Self::Autostart => join(Base::Config, &["autostart"])?,
+        Self::Icon => join(Base::Data, &["icons"])?,
+        Self::Mime => join(Base::Data, &["mime"])?,
+        Self::SystemdUnit => join(Base::Config, &["systemd", "user"])?,
+      },
+      Platform::MacOS => {
+        let components: &[&str] = match self {
+          Self::Application => &["Applications"],
+          Self::Autostart => &["Library", "LaunchAgents"],
+          Self::Icon | Self::Mime | Self::SystemdUnit => {
+            return Err(Error::NotDefinedByPlatformStandard)
+          }
+        };
+        Resolved::new(platform.join(&env.home, components), Source::Default)
+      }
+      Platform::Windows => {
+        let programs = ["Microsoft", "Windows", "Start Menu", "Programs"];
+        match self {
+          Self::Application => join(Base::Data, &programs)?,
+          Self::Autostart => join(Base::Data, &programs)?.join(platform, &["Startup"]),
+          Self::Icon | Self::Mime | Self::SystemdUnit => {
+            return Err(Error::NotDefinedByPlatformStandard)
+          }
+        }
+      }
+      Platform::Wasm => return Err(Error::NotDefinedByPlatformStandard),
+    };
+
+    Ok(resolved)
+  }
+}
@@ -1,11 +1,17 @@
+use crate::platform::{
+  secure_temp, Base, Fallback, Platform, Resolved, Resolver, Source, Status, System, SECURE_TEMP,
+};
 use crate::{Error, Result};
 use camino::Utf8PathBuf;
 use directories::ProjectDirs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
 use structopt::StructOpt;
-use strum::{Display, EnumIter, EnumProperty, EnumString};
+use strum::{Display, EnumIter, EnumProperty, EnumString, EnumVariantNames, VariantNames};
 
-#[derive(Debug, PartialEq, Eq, Hash, Display, StructOpt, EnumString, EnumIter, EnumProperty)]
+#[derive(
+  Debug, Clone, Copy, PartialEq, Eq, Hash, Display, StructOpt, EnumString, EnumIter, EnumProperty,
+)]
 #[cfg_attr(
   feature = "serde1",
   derive(serde::Serialize, serde::Deserialize),
@@ -18,53 +24,257 @@ use strum::{Display, EnumIter, EnumProperty, EnumString};
 )]
 pub enum Project {
   #[structopt(about = "the project's cache directory")]
-  #[strum(props(linux = "supported", macos = "supported", windows = "supported"))]
+  #[strum(props(
+    linux = "supported",
+    macos = "supported",
+    windows = "supported",
+    linux_service = "supported",
+    macos_service = "unsupported",
+    windows_service = "unsupported",
+    linux_system = "supported",
+    macos_system = "supported",
+    windows_system = "supported",
+    linux_attributes = "sandbox-redirected",
+    macos_attributes = "os-may-purge,sandbox-redirected",
+    windows_attributes = "sandbox-redirected"
+  ))]
   Cache,
   #[structopt(about = "the project's config directory")]
-  #[strum(props(linux = "supported", macos = "supported", windows = "supported"))]
+  #[strum(props(
+    linux = "supported",
+    macos = "supported",
+    windows = "supported",
+    linux_service = "supported",
+    macos_service = "unsupported",
+    windows_service = "unsupported",
+    linux_system = "supported",
+    macos_system = "supported",
+    windows_system = "supported",
+    linux_attributes = "backed-up,sandbox-redirected",
+    macos_attributes = "backed-up,sandbox-redirected",
+    windows_attributes = "roams,sandbox-redirected"
+  ))]
   Config,
   #[structopt(about = "the project's data directory")]
-  #[strum(props(linux = "supported", macos = "supported", windows = "supported"))]
+  #[strum(props(
+    linux = "supported",
+    macos = "supported",
+    windows = "supported",
+    linux_service = "supported",
+    macos_service = "unsupported",
+    windows_service = "unsupported",
+    linux_system = "supported",
+    macos_system = "supported",
+    windows_system = "supported",
+    linux_attributes = "backed-up,sandbox-redirected",
+    macos_attributes = "backed-up,sandbox-redirected",
+    windows_attributes = "roams,sandbox-redirected"
+  ))]
   Data,
   #[structopt(about = "the project's local data directory")]
-  #[strum(props(linux = "supported", macos = "supported", windows = "supported"))]
+  #[strum(props(
+    linux = "supported",
+    macos = "supported",
+    windows = "supported",
+    linux_service = "supported",
+    macos_service = "unsupported",
+    windows_service = "unsupported",
+    linux_system = "supported",
+    macos_system = "supported",
+    windows_system = "supported",
+    linux_attributes = "backed-up,sandbox-redirected",
+    macos_attributes = "backed-up,sandbox-redirected",
+    windows_attributes = "sandbox-redirected"
+  ))]
   DataLocal,
+  #[structopt(about = "the project's log directory")]
+  #[strum(props(
+    linux = "supported",
+    macos = "supported",
+    windows = "supported",
+    linux_service = "supported",
+    macos_service = "unsupported",
+    windows_service = "unsupported",
+    linux_system = "supported",
+    macos_system = "supported",
+    windows_system = "supported",
+    linux_attributes = "sandbox-redirected",
+    macos_attributes = "sandbox-redirected",
+    windows_attributes = "sandbox-redirected"
+  ))]
+  Log,
   #[structopt(about = "the project's preference directory")]
-  #[strum(props(linux = "supported", macos = "supported", windows = "supported"))]
+  #[strum(props(
+    linux = "supported",
+    macos = "supported",
+    windows = "supported",
+    linux_service = "supported",
+    macos_service = "unsupported",
+    windows_service = "unsupported",
+    linux_system = "supported",
+    macos_system = "supported",
+    windows_system = "supported",
+    linux_attributes = "backed-up,sandbox-redirected",
+    macos_attributes = "backed-up,sandbox-redirected",
+    windows_attributes = "roams,sandbox-redirected"
+  ))]
   Preference,
   #[structopt(about = "the project's path fragment")]
   PathFragment,
   #[structopt(about = "the project's runtime directory")]
-  #[strum(props(linux = "supported", macos = "unsupported", windows = "unsupported"))]
+  #[strum(props(
+    linux = "supported",
+    macos = "unsupported",
+    windows = "unsupported",
+    linux_service = "supported",
+    macos_service = "unsupported",
+    windows_service = "unsupported",
+    linux_system = "supported",
+    macos_system = "unsupported",
+    windows_system = "unsupported",
+    linux_attributes = "os-may-purge"
+  ))]
   Runtime,
   #[structopt(about = "the project's state directory")]
-  #[strum(props(linux = "supported", macos = "unsupported", windows = "unsupported"))]
+  #[strum(props(
+    linux = "supported",
+    macos = "unsupported",
+    windows = "unsupported",
+    linux_service = "supported",
+    macos_service = "unsupported",
+    windows_service = "unsupported",
+    linux_system = "supported",
+    macos_system = "unsupported",
+    windows_system = "unsupported",
+    linux_attributes = "sandbox-redirected"
+  ))]
   State,
 }
 
+const QUALIFIER_HELP: &str = "The reverse domain name notation of the application, excluding the organization or application name itself.";
+const ORGANIZATION_HELP: &str =
+  "The name of the organization that develops this application, or for which the application is developed.";
+const APPLICATION_HELP: &str = "The name of the application itself.";
+const SCOPE_HELP: &str =
+  "Who the directories are for: a user, a service managed by systemd, or the whole machine.";
+
 #[derive(Debug, StructOpt, Clone)]
 pub struct ProjectOptions {
   #[structopt(
     long = "project-qualifier",
     env = "PROJECT_QUALIFIER",
     value_name = "string",
-    help = "The reverse domain name notation of the application, excluding the organization or application name itself."
+    help = QUALIFIER_HELP
   )]
   pub qualifier: Option<String>,
   #[structopt(
     long = "project-organization",
     env = "PROJECT_ORGANIZATION",
     value_name = "string",
-    help = "The name of the organization that develops this application, or for which the application is developed."
+    help = ORGANIZATION_HELP
   )]
   pub organization: Option<String>,
   #[structopt(
     long = "project-application",
     env = "PROJECT_APPLICATION",
     value_name = "string",
-    help = "The name of the application itself."
+    help = APPLICATION_HELP
   )]
   pub application: String,
+  #[structopt(
+    long = "scope",
+    env = "PROJECT_SCOPE",
+    value_name = "scope",
+    default_value,
+    possible_values = Scope::VARIANTS,
+    help = SCOPE_HELP
+  )]
+  pub scope: Scope,
+}
+
+// The same options for commands where the project is optional. This is a
+// plain comment, as structopt would use a doc comment as the description of
+// every command that flattens these options. Commands with a `kind` argument
+// still require the application for project paths.
+#[derive(Debug, Default, StructOpt, Clone)]
+pub struct OptionalProjectOptions {
+  #[structopt(
+    long = "project-qualifier",
+    env = "PROJECT_QUALIFIER",
+    value_name = "string",
+    help = QUALIFIER_HELP
+  )]
+  pub qualifier: Option<String>,
+  #[structopt(
+    long = "project-organization",
+    env = "PROJECT_ORGANIZATION",
+    value_name = "string",
+    help = ORGANIZATION_HELP
+  )]
+  pub organization: Option<String>,
+  #[structopt(
+    long = "project-application",
+    env = "PROJECT_APPLICATION",
+    value_name = "string",
+    required_if("kind", "project"),
+    help = APPLICATION_HELP
+  )]
+  pub application: Option<String>,
+  #[structopt(
+    long = "scope",
+    env = "PROJECT_SCOPE",
+    value_name = "scope",
+    default_value,
+    possible_values = Scope::VARIANTS,
+    help = SCOPE_HELP
+  )]
+  pub scope: Scope,
+}
+
+impl OptionalProjectOptions {
+  /// The project options, if an application was given.
+  pub fn project(&self) -> Option<ProjectOptions> {
+    let Self {
+      qualifier,
+      organization,
+      application,
+      scope,
+    } = self.clone();
+    application.map(|application| ProjectOptions {
+      qualifier,
+      organization,
+      application,
+      scope,
+    })
+  }
+}
+
+#[derive(
+  Debug,
+  Default,
+  Clone,
+  Copy,
+  PartialEq,
+  Eq,
+  PartialOrd,
+  Ord,
+  Hash,
+  Display,
+  EnumString,
+  EnumIter,
+  EnumVariantNames,
+)]
+#[cfg_attr(
+  feature = "serde1",
+  derive(serde::Serialize, serde::Deserialize),
+  serde(rename_all = "kebab-case")
+)]
+#[strum(serialize_all = "kebab-case")]
+pub enum Scope {
+  #[default]
+  User,
+  Service,
+  System,
 }
 
 impl Project {
@@ -72,27 +282,230 @@ impl Project {
     ProjectDirs::try_from(options)
   }
 
-  pub(crate) fn path_buf(&self, options: &ProjectOptions) -> Result<PathBuf> {
-    Self::dirs(options).and_then(|project| {
-      match self {
-        Self::Cache => Some(project.cache_dir()),
-        Self::Config => Some(project.config_dir()),
-        Self::Data => Some(project.data_dir()),
-        Self::DataLocal => Some(project.data_local_dir()),
-        Self::Preference => Some(project.preference_dir()),
-        Self::PathFragment => Some(project.project_path()),
-        Self::Runtime => project.runtime_dir(),
-        Self::State => project.state_dir(),
+  /// Resolves the path for the current user on this machine, as
+  /// `resolve` does with `Resolver::from_process`.
+  pub fn utf8_path_buf(&self, options: &ProjectOptions) -> Result<Utf8PathBuf> {
+    let path = self.resolve(&Resolver::from_process()?, options)?;
+    Ok(Utf8PathBuf::try_from(path)?)
+  }
+
+  pub fn resolve(&self, resolver: &Resolver, options: &ProjectOptions) -> Result<PathBuf> {
+    self
+      .explain(resolver, options)
+      .map(|resolved| resolved.path)
+  }
+
+  /// Resolves the path along with the inputs that decided it.
+  pub fn explain(&self, resolver: &Resolver, options: &ProjectOptions) -> Result<Resolved> {
+    let platform = &resolver.platform;
+    let fragment = options.fragment(platform);
+    let base = |path: Base| path.explain(resolver);
+    let fragment_only = || Resolved::new(platform.join(Path::new(""), &fragment), Source::Project);
+    match (options.scope, self) {
+      (_, Self::PathFragment) => return Ok(fragment_only()),
+      (Scope::Service, _) => return self.explain_service(resolver, &fragment),
+      (Scope::System, _) => return self.explain_system(resolver, &fragment),
+      (Scope::User, _) => (),
+    }
+
+    let resolved = match platform {
+      Platform::Windows => {
+        let local = || base(Base::DataLocal).map(|path| path.join(platform, &fragment));
+        let roaming = || base(Base::Data).map(|path| path.join(platform, &fragment));
+        match self {
+          Self::Cache => local()?.join(platform, &["cache"]),
+          Self::Config | Self::Preference => roaming()?.join(platform, &["config"]),
+          Self::Data => roaming()?.join(platform, &["data"]),
+          Self::DataLocal => local()?.join(platform, &["data"]),
+          Self::PathFragment => fragment_only(),
+          Self::Log => local()?.join(platform, &["Logs"]),
+          Self::Runtime | Self::State => return Err(Error::NotDefinedByPlatformStandard),
+        }
+      }
+      _ => {
+        let path = match self {
+          Self::Cache => base(Base::Cache)?,
+          Self::Config => base(Base::Config)?,
+          Self::Data => base(Base::Data)?,
+          Self::DataLocal => base(Base::DataLocal)?,
+          Self::Log => base(Base::Log)?,
+          Self::Preference => base(Base::Preference)?,
+          Self::PathFragment => return Ok(fragment_only()),
+          Self::Runtime => base(Base::Runtime)?,
+          Self::State => base(Base::State)?,
+        };
+        let path = path.join(platform, &fragment);
+        match (platform, self) {
+          (Platform::Linux, Self::Log) => path.join(platform, &["log"]),
+          _ => path,
+        }
+      }
+    };
+
+    Ok(resolved)
+  }
+
+  /// systemd passes the directories configured for a service in environment
+  /// variables, and the FHS locations are used when they are unset. Services
+  /// have no separate data directory, so data is kept with the state.
+  fn explain_service(&self, resolver: &Resolver, fragment: &[String]) -> Result<Resolved> {
+    let Resolver { platform, env, .. } = resolver;
+    if *platform != Platform::Linux {
+      return Err(Error::NotDefinedByPlatformStandard);
+    }
+
+    let (name, default) = match self {
+      Self::Cache => ("CACHE_DIRECTORY", "/var/cache"),
+      Self::Config | Self::Preference => ("CONFIGURATION_DIRECTORY", "/etc"),
+      Self::Data | Self::DataLocal | Self::State => ("STATE_DIRECTORY", "/var/lib"),
+      Self::Log => ("LOGS_DIRECTORY", "/var/log"),
+      Self::PathFragment => return Err(Error::NotDefinedByPlatformStandard),
+      Self::Runtime => ("RUNTIME_DIRECTORY", "/run"),
+    };
+
+    let mut rejected = Vec::new();
+    let resolved = env
+      .absolute_var(name, &mut rejected)
+      .map(|mut resolved| {
+        // Every directory configured for the service is listed, separated by
+        // colons, and the first one is the service's own.
+        let paths = resolved.path.to_string_lossy().into_owned();
+        resolved.path = PathBuf::from(paths.split(':').next().unwrap_or_default());
+        resolved
+      })
+      .unwrap_or_else(|| {
+        Resolved::new(platform.join(Path::new(default), fragment), Source::Default)
+      });
+
+    Ok(resolved.rejecting(rejected))
+  }
+
+  /// Machine-wide installs keep their files in the system's directories,
+  /// rather than in the user's.
+  fn explain_system(&self, resolver: &Resolver, fragment: &[String]) -> Result<Resolved> {
+    let Resolver { platform, env, .. } = resolver;
+    let unsupported = Err(Error::NotDefinedByPlatformStandard);
+    let resolved = match platform {
+      Platform::Linux => {
+        let base = match self {
+          Self::Cache => "/var/cache",
+          Self::Config | Self::Preference => "/etc",
+          Self::Data => "/usr/share",
+          Self::DataLocal | Self::State => "/var/lib",
+          Self::Log => "/var/log",
+          Self::PathFragment => return unsupported,
+          Self::Runtime => "/run",
+        };
+        Resolved::new(platform.join(Path::new(base), fragment), Source::Default)
+      }
+      Platform::MacOS => {
+        let base: &[&str] = match self {
+          Self::Cache => &["Library", "Caches"],
+          Self::Config | Self::Data | Self::DataLocal => &["Library", "Application Support"],
+          Self::Log => &["Library", "Logs"],
+          Self::Preference => &["Library", "Preferences"],
+          Self::PathFragment | Self::Runtime | Self::State => return unsupported,
+        };
+        let base = platform.join(Path::new("/"), base);
+        Resolved::new(platform.join(&base, fragment), Source::Default)
+      }
+      Platform::Windows => {
+        let directory = match self {
+          Self::Cache => "cache",
+          Self::Config | Self::Preference => "config",
+          Self::Data | Self::DataLocal => "data",
+          Self::Log => "Logs",
+          Self::PathFragment | Self::Runtime | Self::State => return unsupported,
+        };
+        env
+          .system_folder("ProgramData", &["ProgramData"])
+          .join(platform, fragment)
+          .join(platform, &[directory])
+      }
+      Platform::Wasm => return unsupported,
+    };
+
+    Ok(resolved)
+  }
+
+  /// Whether the platform defines this path for projects of the given scope.
+  pub(crate) fn scoped_status(&self, platform: &Platform, scope: &Scope) -> Status {
+    let prop: &'static str = platform.into();
+    match scope {
+      Scope::User => self.get_str(prop),
+      scope => self.get_str(&format!("{prop}_{scope}")),
+    }
+    .into()
+  }
+
+  /// Resolves the path, or the first fallback that the platform defines.
+  pub fn explain_with(
+    &self,
+    resolver: &Resolver,
+    options: &ProjectOptions,
+    fallback: &Fallback,
+  ) -> Result<Resolved> {
+    fallback.validate::<Self>()?;
+    let resolved = self.explain(resolver, options);
+    fallback.apply(&self.to_string(), resolved, |name| match name {
+      SECURE_TEMP => {
+        let fragment = options.fragment(&resolver.platform);
+        Ok(secure_temp(resolver)?.join(&resolver.platform, &fragment))
       }
-      .ok_or(Error::NotDefinedByPlatformStandard)
-      .map(|path| path.to_path_buf())
+      name => Self::from_str(name)
+        .map_err(|_| Error::InvalidPathName(name.to_string()))?
+        .explain(resolver, options),
     })
   }
 
-  pub fn utf8_path_buf(&self, options: &ProjectOptions) -> Result<Utf8PathBuf> {
-    self
-      .path_buf(options)
-      .and_then(|path| Ok(Utf8PathBuf::try_from(path)?))
+  /// The directories to search for existing files, in order of precedence.
+  pub fn search_paths(
+    &self,
+    resolver: &Resolver,
+    options: &ProjectOptions,
+  ) -> Result<Vec<PathBuf>> {
+    let system = match self {
+      Self::Config | Self::Preference => Some(System::Config),
+      Self::Data | Self::DataLocal => Some(System::Data),
+      _ => None,
+    };
+    let path = self.resolve(resolver, options);
+    super::search_paths(path, system, resolver, Some(options))
+  }
+}
+
+impl ProjectOptions {
+  /// The components of the project's path fragment, following the naming
+  /// conventions of the given platform.
+  pub(crate) fn fragment(&self, platform: &Platform) -> Vec<String> {
+    let Self {
+      qualifier,
+      organization,
+      application,
+      ..
+    } = self;
+    match platform {
+      Platform::MacOS => {
+        let parts = [
+          qualifier.clone().unwrap_or_default(),
+          organization.clone().unwrap_or_default().replace(' ', "-"),
+          application.replace(' ', "-"),
+        ];
+        let parts: Vec<String> = parts.into_iter().filter(|part| !part.is_empty()).collect();
+        vec![parts.join(".")]
+      }
+      Platform::Windows => [
+        organization.clone().unwrap_or_default(),
+        application.clone(),
+      ]
+      .into_iter()
+      .filter(|part| !part.is_empty())
+      .collect(),
+      _ => vec![application
+        .split_whitespace()
+        .map(str::to_lowercase)
+        .collect::<String>()],
+    }
   }
 }
 
@@ -103,6 +516,7 @@ impl TryFrom<&ProjectOptions> for ProjectDirs {
       qualifier,
       organization,
       application,
+      ..
     }: &ProjectOptions,
   ) -> Result<Self> {
     let qualifier = match qualifier {
@@ -116,3 +530,98 @@ impl TryFrom<&ProjectOptions> for ProjectDirs {
     ProjectDirs::from(qualifier, organization, application).ok_or(Error::InvalidHomeDirectory)
   }
 }
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::platform::testing::project_options;
+  use crate::platform::Environment;
+
+  fn options(qualifier: Option<&str>, organization: Option<&str>, scope: Scope) -> ProjectOptions {
+    ProjectOptions {
+      qualifier: qualifier.map(String::from),
+      organization: organization.map(String::from),
+      scope,
+      ..project_options("Nifty Gate")
+    }
+  }
+
+  #[test]
+  fn linux_uses_the_lowercase_application() {
+    let env = Environment::new("/home/alice").with_var("XDG_STATE_HOME", "/var/alice");
+    let resolver = Resolver::new(Platform::Linux, env);
+    let options = options(Some("com"), Some("Acme"), Scope::User);
+    assert_eq!(
+      Project::Config.resolve(&resolver, &options).unwrap(),
+      PathBuf::from("/home/alice/.config/niftygate")
+    );
+    assert_eq!(
+      Project::Log.resolve(&resolver, &options).unwrap(),
+      PathBuf::from("/var/alice/niftygate/log")
+    );
+  }
+
+  #[test]
+  fn macos_uses_the_bundle_identifier() {
+    let resolver = Resolver::new(Platform::MacOS, Environment::new("/Users/alice"));
+    let options = options(Some("com"), Some("Acme Corp"), Scope::User);
+    assert_eq!(
+      Project::PathFragment.resolve(&resolver, &options).unwrap(),
+      PathBuf::from("com.Acme-Corp.Nifty-Gate")
+    );
+    assert_eq!(
+      Project::Cache.resolve(&resolver, &options).unwrap(),
+      PathBuf::from("/Users/alice/Library/Caches/com.Acme-Corp.Nifty-Gate")
+    );
+  }
+
+  #[test]
+  fn windows_uses_the_organization_and_application() {
+    let env = Environment::new("/home/alice").with_var("USERPROFILE", r"C:\Users\alice");
+    let resolver = Resolver::new(Platform::Windows, env);
+    let options = options(Some("com"), Some("Acme Corp"), Scope::User);
+    assert_eq!(
+      Project::PathFragment.resolve(&resolver, &options).unwrap(),
+      PathBuf::from(r"Acme Corp\Nifty Gate")
+    );
+    assert_eq!(
+      Project::Config.resolve(&resolver, &options).unwrap(),
+      PathBuf::from(r"C:\Users\alice\AppData\Roaming\Acme Corp\Nifty Gate\config")
+    );
+    assert_eq!(
+      Project::Log.resolve(&resolver, &options).unwrap(),
+      PathBuf::from(r"C:\Users\alice\AppData\Local\Acme Corp\Nifty Gate\Logs")
+    );
+    assert!(matches!(
+      Project::State.resolve(&resolver, &options),
+      Err(Error::NotDefinedByPlatformStandard)
+    ));
+  }
+
+  #[test]
+  fn service_scope_reads_systemd_variables() {
+    let env =
+      Environment::new("/home/alice").with_var("STATE_DIRECTORY", "/var/lib/nifty:/var/lib/other");
+    let resolver = Resolver::new(Platform::Linux, env);
+    let options = options(None, None, Scope::Service);
+    assert_eq!(
+      Project::State.resolve(&resolver, &options).unwrap(),
+      PathBuf::from("/var/lib/nifty")
+    );
+    assert_eq!(
+      Project::Cache.resolve(&resolver, &options).unwrap(),
+      PathBuf::from("/var/cache/niftygate")
+    );
+  }
+
+  #[test]
+  fn system_scope_uses_machine_wide_directories() {
+    let env = Environment::new("/home/alice").with_var("ProgramData", r"C:\ProgramData");
+    let resolver = Resolver::new(Platform::Windows, env);
+    let options = options(None, Some("Acme"), Scope::System);
+    assert_eq!(
+      Project::Data.resolve(&resolver, &options).unwrap(),
+      PathBuf::from(r"C:\ProgramData\Acme\Nifty Gate\data")
+    );
+  }
+}
@@ -0,0 +1,189 @@
+use crate::platform::{Platform, ProjectOptions, Rejected, Resolved, Resolver, Source};
+use crate::{Error, Result};
+use std::path::{Path, PathBuf};
+use structopt::StructOpt;
+use strum::{Display, EnumIter, EnumProperty, EnumString};
+
+#[derive(Debug, PartialEq, Eq, Hash, Display, StructOpt, EnumString, EnumIter, EnumProperty)]
+#[cfg_attr(
+  feature = "serde1",
+  derive(serde::Serialize, serde::Deserialize),
+  serde(rename_all = "kebab-case")
+)]
+#[strum(serialize_all = "kebab-case")]
+#[structopt(
+  rename_all = "kebab-case",
+  about = "system-wide search directories, in order of precedence"
+)]
+pub enum System {
+  #[structopt(about = "the system's config directories")]
+  #[strum(props(
+    linux = "supported",
+    macos = "supported",
+    windows = "supported",
+    linux_attributes = "backed-up",
+    macos_attributes = "backed-up",
+    windows_attributes = ""
+  ))]
+  Config,
+  #[structopt(about = "the system's data directories")]
+  #[strum(props(
+    linux = "supported",
+    macos = "supported",
+    windows = "supported",
+    linux_attributes = "",
+    macos_attributes = "backed-up",
+    windows_attributes = ""
+  ))]
+  Data,
+}
+
+impl System {
+  pub fn resolve(
+    &self,
+    resolver: &Resolver,
+    project: Option<&ProjectOptions>,
+  ) -> Result<Vec<PathBuf>> {
+    self
+      .explain(resolver, project)
+      .map(|resolved| resolved.path)
+  }
+
+  /// Resolves the paths along with the inputs that decided them.
+  pub fn explain(
+    &self,
+    resolver: &Resolver,
+    project: Option<&ProjectOptions>,
+  ) -> Result<Resolved<Vec<PathBuf>>> {
+    let Resolver { platform, env, .. } = resolver;
+    let fragment = project
+      .map(|options| options.fragment(platform))
+      .unwrap_or_default();
+
+    let resolved = match platform {
+      Platform::Linux => {
+        let (name, default) = match self {
+          Self::Config => ("XDG_CONFIG_DIRS", "/etc/xdg"),
+          Self::Data => ("XDG_DATA_DIRS", "/usr/local/share:/usr/share"),
+        };
+        // Relative entries are invalid according to the XDG specification,
+        // and should be ignored.
+        let parse = |value: &str| -> (Vec<String>, Vec<String>) {
+          value
+            .split(':')
+            .filter(|path| !path.is_empty())
+            .map(String::from)
+            .partition(|path| path.starts_with('/'))
+        };
+        let (mut paths, relative, mut source) = match env.var(name) {
+          Some(value) => {
+            let (paths, relative) = parse(&value.to_string_lossy());
+            (paths, relative, Source::variable(name, value))
+          }
+          None => (Vec::new(), Vec::new(), Source::Default),
+        };
+        // A variable without any valid entries is treated as if it were
+        // unset, so the defaults of the specification are used.
+        if paths.is_empty() {
+          (paths, _) = parse(default);
+          source = Source::Default;
+        }
+        let paths = paths
+          .into_iter()
+          .map(|path| platform.join(Path::new(&path), &fragment))
+          .collect();
+        let rejected = relative
+          .into_iter()
+          .map(|path| {
+            let source = Source::variable(name, path.as_ref());
+            Rejected::new(source, "not an absolute path")
+          })
+          .collect();
+        Some(Resolved::new(paths, source).rejecting(rejected))
+      }
+      Platform::MacOS => {
+        let path = Path::new("/Library/Application Support");
+        let paths = vec![platform.join(path, &fragment)];
+        Some(Resolved::new(paths, Source::Default))
+      }
+      Platform::Windows => {
+        let resolved = env.system_folder("ProgramData", &["ProgramData"]);
+        let resolved = match project {
+          None => resolved,
+          Some(_) => {
+            let resolved = resolved.join(platform, &fragment);
+            match self {
+              Self::Config => resolved.join(platform, &["config"]),
+              Self::Data => resolved.join(platform, &["data"]),
+            }
+          }
+        };
+        Some(resolved.listed())
+      }
+      Platform::Wasm => None,
+    };
+
+    resolved
+      .filter(|resolved| !resolved.path.is_empty())
+      .ok_or(Error::NotDefinedByPlatformStandard)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::platform::Environment;
+
+  fn linux(env: Environment) -> Resolver {
+    Resolver::new(Platform::Linux, env)
+  }
+
+  fn paths(paths: &[&str]) -> Vec<PathBuf> {
+    paths.iter().map(PathBuf::from).collect()
+  }
+
+  #[test]
+  fn unset_variables_use_the_defaults() {
+    let resolver = linux(Environment::new("/home/alice"));
+    let resolved = System::Data.explain(&resolver, None).unwrap();
+    assert_eq!(resolved.path, paths(&["/usr/local/share", "/usr/share"]));
+    assert_eq!(resolved.source, Source::Default);
+    assert!(resolved.rejected.is_empty());
+  }
+
+  #[test]
+  fn empty_entries_are_skipped() {
+    let value = ":/opt/share::/usr/share:";
+    let env = Environment::new("/home/alice").with_var("XDG_DATA_DIRS", value);
+    let resolved = System::Data.explain(&linux(env), None).unwrap();
+    assert_eq!(resolved.path, paths(&["/opt/share", "/usr/share"]));
+    assert_eq!(
+      resolved.source,
+      Source::variable("XDG_DATA_DIRS", value.as_ref())
+    );
+    assert!(resolved.rejected.is_empty());
+  }
+
+  #[test]
+  fn relative_entries_are_rejected() {
+    let env = Environment::new("/home/alice").with_var("XDG_CONFIG_DIRS", "/etc/site:xdg:/etc/xdg");
+    let resolved = System::Config.explain(&linux(env), None).unwrap();
+    assert_eq!(resolved.path, paths(&["/etc/site", "/etc/xdg"]));
+    assert_eq!(
+      resolved.rejected,
+      vec![Rejected::new(
+        Source::variable("XDG_CONFIG_DIRS", "xdg".as_ref()),
+        "not an absolute path"
+      )]
+    );
+  }
+
+  #[test]
+  fn the_defaults_are_used_when_every_entry_is_rejected() {
+    let env = Environment::new("/home/alice").with_var("XDG_CONFIG_DIRS", "xdg:etc/xdg");
+    let resolved = System::Config.explain(&linux(env), None).unwrap();
+    assert_eq!(resolved.path, paths(&["/etc/xdg"]));
+    assert_eq!(resolved.source, Source::Default);
+    assert_eq!(resolved.rejected.len(), 2);
+  }
+}
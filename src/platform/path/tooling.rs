@@ -0,0 +1,202 @@
+use crate::platform::{Base, Platform, Resolved, Resolver};
+use crate::{Error, Result};
+use std::path::PathBuf;
+use structopt::StructOpt;
+use strum::{Display, EnumIter, EnumProperty, EnumString, EnumVariantNames, VariantNames};
+
+#[derive(Debug, PartialEq, Eq, Hash, Display, StructOpt, EnumString, EnumIter, EnumProperty)]
+#[cfg_attr(
+  feature = "serde1",
+  derive(serde::Serialize, serde::Deserialize),
+  serde(rename_all = "kebab-case")
+)]
+#[strum(serialize_all = "kebab-case")]
+#[structopt(
+  rename_all = "kebab-case",
+  about = "directories for installing command-line tools"
+)]
+pub enum Tooling {
+  #[structopt(about = "the user's shell completion directory")]
+  #[strum(props(
+    linux = "supported",
+    macos = "unsupported",
+    windows = "unsupported",
+    linux_attributes = "backed-up,sandbox-redirected"
+  ))]
+  Completion,
+  #[structopt(about = "the user's executable directory")]
+  #[strum(props(
+    linux = "supported",
+    macos = "unsupported",
+    windows = "unsupported",
+    linux_attributes = "backed-up,user-visible,sandbox-redirected"
+  ))]
+  Executable,
+  #[structopt(about = "the user's manual page directory")]
+  #[strum(props(
+    linux = "supported",
+    macos = "unsupported",
+    windows = "unsupported",
+    linux_attributes = "backed-up,sandbox-redirected"
+  ))]
+  Man,
+}
+
+#[derive(Debug, Clone)]
+pub struct ToolingOptions {
+  /// The shell that completions are installed for.
+  pub shell: CompletionShell,
+  /// The section that manual pages are installed for.
+  pub section: String,
+}
+
+impl Default for ToolingOptions {
+  fn default() -> Self {
+    Self {
+      shell: CompletionShell::default(),
+      section: "1".to_string(),
+    }
+  }
+}
+
+#[derive(
+  Debug, Default, Clone, Copy, PartialEq, Eq, Hash, Display, EnumString, EnumVariantNames,
+)]
+#[strum(serialize_all = "kebab-case")]
+pub enum CompletionShell {
+  #[default]
+  Bash,
+  Zsh,
+  Fish,
+}
+
+/// Tooling paths as given on the command line, where each path takes only
+/// the options that apply to it.
+#[derive(Debug, StructOpt)]
+#[structopt(
+  rename_all = "kebab-case",
+  about = "directories for installing command-line tools"
+)]
+pub(crate) enum ToolingPath {
+  #[structopt(about = "the user's shell completion directory")]
+  Completion {
+    #[structopt(
+      long = "shell",
+      value_name = "shell",
+      default_value,
+      possible_values = CompletionShell::VARIANTS,
+      help = "The shell that completions are installed for."
+    )]
+    shell: CompletionShell,
+  },
+  #[structopt(about = "the user's executable directory")]
+  Executable,
+  #[structopt(about = "the user's manual page directory")]
+  Man {
+    #[structopt(
+      long = "section",
+      value_name = "section",
+      default_value = "1",
+      help = "The section that manual pages are installed for."
+    )]
+    section: String,
+  },
+}
+
+impl From<ToolingPath> for (Tooling, ToolingOptions) {
+  fn from(path: ToolingPath) -> Self {
+    let options = ToolingOptions::default();
+    match path {
+      ToolingPath::Completion { shell } => {
+        (Tooling::Completion, ToolingOptions { shell, ..options })
+      }
+      ToolingPath::Executable => (Tooling::Executable, options),
+      ToolingPath::Man { section } => (Tooling::Man, ToolingOptions { section, ..options }),
+    }
+  }
+}
+
+impl Tooling {
+  pub fn resolve(&self, resolver: &Resolver, options: &ToolingOptions) -> Result<PathBuf> {
+    self
+      .explain(resolver, options)
+      .map(|resolved| resolved.path)
+  }
+
+  /// Resolves the path along with the inputs that decided it.
+  pub fn explain(&self, resolver: &Resolver, options: &ToolingOptions) -> Result<Resolved> {
+    let Resolver { platform, env, .. } = resolver;
+    if *platform != Platform::Linux {
+      return Err(Error::NotDefinedByPlatformStandard);
+    }
+
+    let data = || Base::Data.explain(resolver);
+    let resolved = match self {
+      Self::Completion => match options.shell {
+        // bash-completion looks in $BASH_COMPLETION_USER_DIR/completions first.
+        CompletionShell::Bash => {
+          let mut rejected = Vec::new();
+          let base = match env.absolute_var("BASH_COMPLETION_USER_DIR", &mut rejected) {
+            Some(resolved) => resolved,
+            None => data()?.join(platform, &["bash-completion"]),
+          };
+          base.join(platform, &["completions"]).rejecting(rejected)
+        }
+        CompletionShell::Zsh => data()?.join(platform, &["zsh", "site-functions"]),
+        CompletionShell::Fish => data()?.join(platform, &["fish", "vendor_completions.d"]),
+      },
+      Self::Executable => Base::Executable.explain(resolver)?,
+      Self::Man => {
+        // The section becomes part of a path component, so it must look like
+        // a section, such as 1 or 3p.
+        let section = &options.section;
+        let mut chars = section.chars();
+        let valid = chars.next().is_some_and(|c| c.is_ascii_digit())
+          && chars.all(|c| c.is_ascii_digit() || c.is_ascii_lowercase());
+        if !valid {
+          return Err(Error::InvalidRequest(format!(
+            "manual section must be a digit, optionally followed by lowercase letters or digits: {section}"
+          )));
+        }
+        data()?.join(platform, &["man", &format!("man{section}")])
+      }
+    };
+
+    Ok(resolved)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::platform::Environment;
+
+  fn man(section: &str) -> Result<PathBuf> {
+    let resolver = Resolver::new(Platform::Linux, Environment::new("/home/alice"));
+    let options = ToolingOptions {
+      section: section.to_string(),
+      ..ToolingOptions::default()
+    };
+    Tooling::Man.resolve(&resolver, &options)
+  }
+
+  #[test]
+  fn manual_sections_name_the_directory() {
+    for section in ["1", "3p", "8", "3ssl"] {
+      assert_eq!(
+        man(section).unwrap(),
+        PathBuf::from(format!("/home/alice/.local/share/man/man{section}"))
+      );
+    }
+  }
+
+  #[test]
+  fn manual_sections_are_validated() {
+    for section in ["", "p", "1/../../x", "../1", "1 ", "1P", "/1"] {
+      assert!(
+        matches!(man(section), Err(Error::InvalidRequest(_))),
+        "{section:?}"
+      );
+    }
+  }
+}
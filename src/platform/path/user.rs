@@ -1,3 +1,4 @@
+use crate::platform::{KnownFolder, Platform, Resolved, Resolver, Source};
 use crate::{Error, Result};
 use camino::Utf8PathBuf;
 use directories::UserDirs;
@@ -5,7 +6,9 @@ use std::path::PathBuf;
 use structopt::StructOpt;
 use strum::{Display, EnumIter, EnumProperty, EnumString};
 
-#[derive(Debug, PartialEq, Eq, Hash, Display, StructOpt, EnumString, EnumIter, EnumProperty)]
+#[derive(
+  Debug, Clone, Copy, PartialEq, Eq, Hash, Display, StructOpt, EnumString, EnumIter, EnumProperty,
+)]
 #[cfg_attr(
   feature = "serde1",
   derive(serde::Serialize, serde::Deserialize),
@@ -15,34 +18,102 @@ use strum::{Display, EnumIter, EnumProperty, EnumString};
 #[structopt(rename_all = "kebab-case", about = "user-facing standard directories")]
 pub enum User {
   #[structopt(about = "the user's audio directory")]
-  #[strum(props(linux = "supported", macos = "supported", windows = "supported"))]
+  #[strum(props(
+    linux = "supported",
+    macos = "supported",
+    windows = "supported",
+    linux_attributes = "backed-up,user-visible",
+    macos_attributes = "backed-up,user-visible",
+    windows_attributes = "backed-up,user-visible"
+  ))]
   Audio,
   #[structopt(about = "the user's desktop directory")]
-  #[strum(props(linux = "supported", macos = "supported", windows = "supported"))]
+  #[strum(props(
+    linux = "supported",
+    macos = "supported",
+    windows = "supported",
+    linux_attributes = "backed-up,user-visible",
+    macos_attributes = "backed-up,user-visible",
+    windows_attributes = "backed-up,user-visible"
+  ))]
   Desktop,
   #[structopt(about = "the user's document directory")]
-  #[strum(props(linux = "supported", macos = "supported", windows = "supported"))]
+  #[strum(props(
+    linux = "supported",
+    macos = "supported",
+    windows = "supported",
+    linux_attributes = "backed-up,user-visible",
+    macos_attributes = "backed-up,user-visible",
+    windows_attributes = "backed-up,user-visible"
+  ))]
   Document,
   #[structopt(about = "the user's download directory")]
-  #[strum(props(linux = "supported", macos = "supported", windows = "supported"))]
+  #[strum(props(
+    linux = "supported",
+    macos = "supported",
+    windows = "supported",
+    linux_attributes = "backed-up,user-visible",
+    macos_attributes = "backed-up,user-visible",
+    windows_attributes = "backed-up,user-visible"
+  ))]
   Download,
   #[structopt(about = "the user's font directory")]
-  #[strum(props(linux = "supported", macos = "supported", windows = "unsupported"))]
+  #[strum(props(
+    linux = "supported",
+    macos = "supported",
+    windows = "unsupported",
+    linux_attributes = "backed-up",
+    macos_attributes = "backed-up"
+  ))]
   Font,
   #[structopt(about = "the user's home directory")]
-  #[strum(props(linux = "supported", macos = "supported", windows = "supported"))]
+  #[strum(props(
+    linux = "supported",
+    macos = "supported",
+    windows = "supported",
+    linux_attributes = "backed-up,user-visible",
+    macos_attributes = "backed-up,user-visible",
+    windows_attributes = "backed-up,user-visible"
+  ))]
   Home,
   #[structopt(about = "the user's picture directory")]
-  #[strum(props(linux = "supported", macos = "supported", windows = "supported"))]
+  #[strum(props(
+    linux = "supported",
+    macos = "supported",
+    windows = "supported",
+    linux_attributes = "backed-up,user-visible",
+    macos_attributes = "backed-up,user-visible",
+    windows_attributes = "backed-up,user-visible"
+  ))]
   Picture,
   #[structopt(about = "the user's public directory")]
-  #[strum(props(linux = "supported", macos = "supported", windows = "supported"))]
+  #[strum(props(
+    linux = "supported",
+    macos = "supported",
+    windows = "supported",
+    linux_attributes = "backed-up,user-visible",
+    macos_attributes = "backed-up,user-visible",
+    windows_attributes = "backed-up,user-visible"
+  ))]
   Public,
   #[structopt(about = "the user's template directory")]
-  #[strum(props(linux = "supported", macos = "unsupported", windows = "supported"))]
+  #[strum(props(
+    linux = "supported",
+    macos = "unsupported",
+    windows = "supported",
+    linux_attributes = "backed-up,user-visible",
+    windows_attributes = "backed-up,user-visible"
+  ))]
   Template,
   #[structopt(about = "the user's video directory")]
-  #[strum(props(linux = "supported", macos = "supported", windows = "supported"))]
+  #[strum(props(
+    linux = "supported",
+    macos = "supported",
+    windows = "supported",
+    linux_attributes = "backed-up,user-visible",
+    macos_attributes = "backed-up,user-visible",
+    windows_attributes = "backed-up,user-visible"
+  ))]
   Video,
 }
 
@@ -51,28 +122,171 @@ impl User {
     UserDirs::new().ok_or(Error::InvalidHomeDirectory)
   }
 
-  pub(crate) fn path_buf(&self) -> Result<PathBuf> {
-    Self::dirs().and_then(|user| {
-      match self {
-        Self::Audio => user.audio_dir(),
-        Self::Desktop => user.desktop_dir(),
-        Self::Document => user.document_dir(),
-        Self::Download => user.download_dir(),
-        Self::Font => user.font_dir(),
-        Self::Home => Some(user.home_dir()),
-        Self::Picture => user.picture_dir(),
-        Self::Public => user.public_dir(),
-        Self::Template => user.template_dir(),
-        Self::Video => user.video_dir(),
+  /// Resolves the path for the current user on this machine, as
+  /// `resolve` does with `Resolver::from_process`.
+  pub fn utf8_path_buf(&self) -> Result<Utf8PathBuf> {
+    let path = self.resolve(&Resolver::from_process()?)?;
+    Ok(Utf8PathBuf::try_from(path)?)
+  }
+
+  pub fn resolve(&self, resolver: &Resolver) -> Result<PathBuf> {
+    self.explain(resolver).map(|resolved| resolved.path)
+  }
+
+  /// Resolves the path along with the inputs that decided it.
+  pub fn explain(&self, resolver: &Resolver) -> Result<Resolved> {
+    let Resolver { platform, env, .. } = resolver;
+    match platform {
+      Platform::Linux => match self {
+        Self::Audio => resolver.xdg_user_dir("MUSIC"),
+        Self::Desktop => resolver.xdg_user_dir("DESKTOP"),
+        Self::Document => resolver.xdg_user_dir("DOCUMENTS"),
+        Self::Download => resolver.xdg_user_dir("DOWNLOAD"),
+        Self::Font => Some(
+          resolver
+            .xdg_home("XDG_DATA_HOME", &[".local", "share"])
+            .join(platform, &["fonts"]),
+        ),
+        Self::Home => Some(resolver.user_home()),
+        Self::Picture => resolver.xdg_user_dir("PICTURES"),
+        Self::Public => resolver.xdg_user_dir("PUBLICSHARE"),
+        Self::Template => resolver.xdg_user_dir("TEMPLATES"),
+        Self::Video => resolver.xdg_user_dir("VIDEOS"),
+      },
+      Platform::MacOS => {
+        let default = |components: &[&str]| {
+          Resolved::new(platform.join(&env.home, components), Source::Default)
+        };
+        match self {
+          Self::Audio => Some(default(&["Music"])),
+          Self::Desktop => Some(default(&["Desktop"])),
+          Self::Document => Some(default(&["Documents"])),
+          Self::Download => Some(default(&["Downloads"])),
+          Self::Font => Some(default(&["Library", "Fonts"])),
+          Self::Home => Some(Resolved::new(env.home.clone(), Source::Home)),
+          Self::Picture => Some(default(&["Pictures"])),
+          Self::Public => Some(default(&["Public"])),
+          Self::Template => None,
+          Self::Video => Some(default(&["Movies"])),
+        }
       }
-      .ok_or(Error::NotDefinedByPlatformStandard)
-      .map(|path| path.to_path_buf())
-    })
+      Platform::Windows => {
+        let folder = |folder: KnownFolder| Some(resolver.known_folder(folder));
+        match self {
+          Self::Audio => folder(KnownFolder::Music),
+          Self::Desktop => folder(KnownFolder::Desktop),
+          Self::Document => folder(KnownFolder::Documents),
+          Self::Download => folder(KnownFolder::Downloads),
+          Self::Font => None,
+          Self::Home => folder(KnownFolder::Profile),
+          Self::Picture => folder(KnownFolder::Pictures),
+          Self::Public => folder(KnownFolder::Public),
+          Self::Template => folder(KnownFolder::Templates),
+          Self::Video => folder(KnownFolder::Videos),
+        }
+      }
+      Platform::Wasm => None,
+    }
+    .ok_or(Error::NotDefinedByPlatformStandard)
   }
+}
 
-  pub fn utf8_path_buf(&self) -> Result<Utf8PathBuf> {
-    self
-      .path_buf()
-      .and_then(|path| Ok(Utf8PathBuf::try_from(path)?))
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::platform::testing::TempDir;
+  use crate::platform::{Environment, Rejected};
+
+  #[test]
+  fn linux_reads_xdg_user_dir_variables() {
+    let env = Environment::new("/home/alice").with_var("XDG_DOCUMENTS_DIR", "/srv/documents");
+    let resolver = Resolver::new(Platform::Linux, env);
+    let resolved = User::Document.explain(&resolver).unwrap();
+    assert_eq!(resolved.path, PathBuf::from("/srv/documents"));
+    assert_eq!(
+      resolved.source,
+      Source::variable("XDG_DOCUMENTS_DIR", "/srv/documents".as_ref())
+    );
+  }
+
+  #[test]
+  fn linux_reads_user_dirs_file() {
+    let home = TempDir::new();
+    let file = home.write(
+      ".config/user-dirs.dirs",
+      "# written by xdg-user-dirs-update\nXDG_MUSIC_DIR=\"$HOME/Tunes\"\nXDG_VIDEOS_DIR=\"Videos\"\n",
+    );
+    let resolver = Resolver::new(Platform::Linux, Environment::new(home.path()));
+
+    let resolved = User::Audio.explain(&resolver).unwrap();
+    assert_eq!(resolved.path, home.path().join("Tunes"));
+    assert_eq!(
+      resolved.source,
+      Source::File {
+        path: file.clone(),
+        line: 2
+      }
+    );
+
+    assert!(matches!(
+      User::Video.explain(&resolver),
+      Err(Error::NotDefinedByPlatformStandard)
+    ));
+  }
+
+  #[test]
+  fn linux_rejects_relative_xdg_variables() {
+    let env = Environment::new("/home/alice").with_var("XDG_DATA_HOME", "share");
+    let resolved = User::Font
+      .explain(&Resolver::new(Platform::Linux, env))
+      .unwrap();
+    assert_eq!(
+      resolved.path,
+      PathBuf::from("/home/alice/.local/share/fonts")
+    );
+    assert_eq!(
+      resolved.rejected,
+      vec![Rejected::new(
+        Source::variable("XDG_DATA_HOME", "share".as_ref()),
+        "not an absolute path"
+      )]
+    );
+  }
+
+  #[test]
+  fn macos_uses_the_home_directory() {
+    let resolver = Resolver::new(Platform::MacOS, Environment::new("/Users/alice"));
+    assert_eq!(
+      User::Video.resolve(&resolver).unwrap(),
+      PathBuf::from("/Users/alice/Movies")
+    );
+    assert!(matches!(
+      User::Template.resolve(&resolver),
+      Err(Error::NotDefinedByPlatformStandard)
+    ));
+  }
+
+  #[test]
+  fn windows_derives_known_folders_from_the_environment() {
+    let env = Environment::new("/home/alice")
+      .with_var("USERPROFILE", r"C:\Users\alice")
+      .with_var("PUBLIC", r"D:\Public");
+    let resolver = Resolver::new(Platform::Windows, env);
+    assert_eq!(
+      User::Document.resolve(&resolver).unwrap(),
+      PathBuf::from(r"C:\Users\alice\Documents")
+    );
+    assert_eq!(
+      User::Public.resolve(&resolver).unwrap(),
+      PathBuf::from(r"D:\Public")
+    );
+    assert_eq!(
+      User::Template.resolve(&resolver).unwrap(),
+      PathBuf::from(r"C:\Users\alice\AppData\Roaming\Microsoft\Windows\Templates")
+    );
+    assert!(matches!(
+      User::Font.resolve(&resolver),
+      Err(Error::NotDefinedByPlatformStandard)
+    ));
   }
 }
@@ -0,0 +1,70 @@
+use crate::output::Format;
+use crate::platform::path::ProjectOptions;
+use crate::platform::PlatformPathKind as Kind;
+use crate::platform::{PlatformPath, Resolver, StructuredPath};
+use crate::Result;
+use camino::Utf8PathBuf;
+use std::collections::HashMap;
+use strum::IntoEnumIterator;
+
+#[derive(Debug)]
+#[cfg_attr(feature = "serde1", derive(serde::Serialize, serde::Deserialize))]
+pub(crate) struct Resolution(HashMap<Kind, HashMap<PlatformPath, StructuredPath>>);
+
+impl Resolution {
+  /// Resolves every path of the given kinds. Project paths are only included
+  /// if project options are given.
+  pub(crate) fn resolve(
+    resolver: &Resolver,
+    kinds: &[Kind],
+    project: Option<&ProjectOptions>,
+  ) -> Self {
+    let mut resolved = HashMap::new();
+
+    for kind in Kind::iter().filter(|kind| kinds.contains(kind)) {
+      if kind == Kind::Project && project.is_none() {
+        continue;
+      }
+
+      let listed = kind == Kind::System;
+      let paths = kind
+        .paths()
+        .into_iter()
+        .map(|path| {
+          let result = path.resolve(resolver, project).and_then(|paths| {
+            let mut paths = paths
+              .into_iter()
+              .map(|path| Ok(Utf8PathBuf::try_from(path)?.into_string()))
+              .collect::<Result<Vec<String>>>()?;
+            Ok(match listed {
+              true => StructuredPath::from(paths),
+              false => StructuredPath::from(paths.remove(0)),
+            })
+          });
+          (path, result.unwrap_or_else(StructuredPath::from))
+        })
+        .collect();
+
+      resolved.insert(kind, paths);
+    }
+
+    Self(resolved)
+  }
+
+  pub(crate) fn render(&self, format: &Format, separator: char) -> Result<String> {
+    format.render(self, || {
+      let mut records = Vec::<String>::new();
+      for (kind, paths) in self.0.iter() {
+        for (path, resolved) in paths {
+          let resolved = match resolved {
+            StructuredPath::Paths(paths) => paths.paths.join(&separator.to_string()),
+            resolved => resolved.render(format)?,
+          };
+          records.push(format!("{kind} {path} {resolved}"));
+        }
+      }
+      records.sort();
+      Ok(records.join("\n"))
+    })
+  }
+}
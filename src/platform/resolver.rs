@@ -0,0 +1,383 @@
+use super::{Environment, KnownFolder, Platform, Rejected, Resolved, Sandbox, Source};
+use crate::{Error, Result};
+use std::ffi::OsString;
+use std::fs;
+use std::path::{Path, PathBuf};
+use structopt::StructOpt;
+use strum::VariantNames;
+
+#[derive(Debug, Clone)]
+pub struct Resolver {
+  pub platform: Platform,
+  pub env: Environment,
+  pub sandbox: Option<Sandbox>,
+  /// Resolve paths as seen from the host, rather than from inside the sandbox.
+  pub host: bool,
+  /// Ask Windows for its Known Folders, rather than deriving them from the
+  /// environment. Only meaningful when resolving for the current user on a
+  /// Windows host.
+  pub known_folders: bool,
+}
+
+impl Resolver {
+  pub fn new(platform: Platform, env: Environment) -> Self {
+    Self {
+      platform,
+      env,
+      sandbox: None,
+      host: false,
+      known_folders: false,
+    }
+  }
+
+  pub fn from_process() -> Result<Self> {
+    let mut resolver = Self::new(Platform::default(), Environment::from_process()?);
+    resolver.known_folders = true;
+    Ok(resolver.detect_sandbox(Path::new("/")))
+  }
+
+  /// Sandboxes are only detected on Linux, with marker files relative to `root`.
+  pub fn detect_sandbox(mut self, root: &Path) -> Self {
+    if self.platform == Platform::Linux {
+      self.sandbox = Sandbox::detect(&self.env, root);
+    }
+    self
+  }
+
+  /// The home directory that user-invisible directories are relative to.
+  pub(crate) fn home(&self) -> Resolved {
+    let sandbox = self.sandbox.as_ref();
+    let home = match self.host {
+      true => sandbox.and_then(Sandbox::host_home),
+      false => sandbox.and_then(Sandbox::home),
+    };
+    match (sandbox, home) {
+      (Some(sandbox), Some(home)) => Resolved::new(home.to_path_buf(), sandbox.source()),
+      _ => Resolved::new(self.env.home.clone(), Source::Home),
+    }
+  }
+
+  /// The home directory that user-facing directories are relative to, which
+  /// is always on the host.
+  pub(crate) fn user_home(&self) -> Resolved {
+    match self.sandbox.as_ref() {
+      Some(sandbox) => sandbox
+        .host_home()
+        .map(|home| Resolved::new(home.to_path_buf(), sandbox.source())),
+      None => None,
+    }
+    .unwrap_or_else(|| Resolved::new(self.env.home.clone(), Source::Home))
+  }
+
+  /// A Windows Known Folder, from Windows itself if `known_folders` is set,
+  /// and otherwise from the environment or the default location.
+  pub(crate) fn known_folder(&self, folder: KnownFolder) -> Resolved {
+    if let Some(path) = self.known_folders.then(|| folder.lookup()).flatten() {
+      let name = folder.to_string();
+      return Resolved::new(path, Source::KnownFolder { name });
+    }
+
+    let env = &self.env;
+    let profile = |components: &[&str]| {
+      let path = Platform::Windows.join(&env.windows_home().path, components);
+      Resolved::new(path, Source::Default)
+    };
+    match folder {
+      KnownFolder::Profile => env.windows_home(),
+      KnownFolder::RoamingAppData => env.known_folder("APPDATA", &["AppData", "Roaming"]),
+      KnownFolder::LocalAppData => env.known_folder("LOCALAPPDATA", &["AppData", "Local"]),
+      KnownFolder::Desktop => profile(&["Desktop"]),
+      KnownFolder::Documents => profile(&["Documents"]),
+      KnownFolder::Downloads => profile(&["Downloads"]),
+      KnownFolder::Music => profile(&["Music"]),
+      KnownFolder::Pictures => profile(&["Pictures"]),
+      KnownFolder::Public => env.system_folder("PUBLIC", &["Users", "Public"]),
+      KnownFolder::Templates => self
+        .known_folder(KnownFolder::RoamingAppData)
+        .join(&Platform::Windows, &["Microsoft", "Windows", "Templates"]),
+      KnownFolder::Videos => profile(&["Videos"]),
+    }
+  }
+
+  pub(crate) fn xdg_home(&self, name: &str, default: &[&str]) -> Resolved {
+    let home = self.home().path;
+    let default = || Resolved::new(Platform::Linux.join(&home, default), Source::Default);
+    let mut rejected = Vec::new();
+    let resolved = match (&self.sandbox, self.host) {
+      (Some(Sandbox::Flatpak { .. }), true) => self
+        .env
+        .absolute_var(&format!("HOST_{name}"), &mut rejected)
+        .unwrap_or_else(default),
+      (
+        Some(
+          sandbox @ Sandbox::AppImage {
+            config: Some(config),
+            ..
+          },
+        ),
+        false,
+      ) if name == "XDG_CONFIG_HOME" => Resolved::new(config.clone(), sandbox.source()),
+      (Some(sandbox), false) => self
+        .env
+        .absolute_var(name, &mut rejected)
+        .or_else(|| {
+          let path = sandbox.xdg_home(name, &home)?;
+          Some(Resolved::new(path, sandbox.source()))
+        })
+        .unwrap_or_else(default),
+      _ => self
+        .env
+        .absolute_var(name, &mut rejected)
+        .unwrap_or_else(default),
+    };
+    resolved.rejecting(rejected)
+  }
+
+  /// User-facing directories are configured by `XDG_*_DIR` variables, or by
+  /// the `user-dirs.dirs` file written by `xdg-user-dirs-update`.
+  pub(crate) fn xdg_user_dir(&self, name: &str) -> Option<Resolved> {
+    let var = format!("XDG_{name}_DIR");
+    let home = self.user_home().path;
+    let mut rejected = Vec::new();
+    let resolved = self.env.absolute_var(&var, &mut rejected).or_else(|| {
+      let path = self
+        .env
+        .absolute_var("XDG_CONFIG_HOME", &mut Vec::new())
+        .map(|resolved| resolved.path)
+        .unwrap_or_else(|| Platform::Linux.join(&home, &[".config"]));
+      let path = Platform::Linux.join(&path, &["user-dirs.dirs"]);
+      let contents = fs::read_to_string(&path).ok()?;
+      let (line, value) = contents
+        .lines()
+        .enumerate()
+        .filter_map(|(index, line)| Some((index + 1, line.split_once('=')?)))
+        .find(|(_, (key, _))| key.trim() == var)
+        .map(|(line, (_, value))| (line, value))?;
+      let source = Source::File { path, line };
+      let value = value
+        .trim()
+        .strip_prefix('"')
+        .and_then(|value| value.strip_suffix('"'))
+        .and_then(|value| match value.strip_prefix("$HOME/") {
+          Some("") => None,
+          Some(relative) => Some(Platform::Linux.join(&home, &[relative])),
+          None if value.starts_with('/') => Some(PathBuf::from(value)),
+          None => None,
+        });
+      match value {
+        Some(path) => Some(Resolved::new(path, source)),
+        None => {
+          rejected.push(Rejected::new(
+            source,
+            "not a quoted absolute path or path relative to $HOME",
+          ));
+          None
+        }
+      }
+    })?;
+    Some(resolved.rejecting(rejected))
+  }
+}
+
+#[derive(Debug, StructOpt)]
+pub struct ResolverOptions {
+  #[structopt(
+    long = "platform",
+    value_name = "platform",
+    possible_values = Platform::VARIANTS,
+    help = "resolve paths as they would be on this platform, rather than the host"
+  )]
+  pub platform: Option<Platform>,
+  #[structopt(
+    long = "home",
+    value_name = "path",
+    help = "resolve paths for this home directory, rather than the current user's"
+  )]
+  pub home: Option<PathBuf>,
+  #[structopt(
+    long = "env-file",
+    value_name = "path",
+    number_of_values = 1,
+    help = "read environment variables from this file (may be repeated)"
+  )]
+  pub env_files: Vec<PathBuf>,
+  #[structopt(
+    long = "ignore-environment",
+    help = "start with an empty environment, rather than the process environment"
+  )]
+  pub ignore_environment: bool,
+  #[structopt(
+    long = "sandbox-root",
+    value_name = "path",
+    default_value = "/",
+    help = "look for sandbox marker files (such as /.flatpak-info) relative to this path"
+  )]
+  pub sandbox_root: PathBuf,
+  #[structopt(
+    long = "host-paths",
+    help = "resolve paths as seen from the host, rather than from inside a sandbox"
+  )]
+  pub host: bool,
+}
+
+impl TryFrom<&ResolverOptions> for Resolver {
+  type Error = crate::Error;
+  fn try_from(
+    ResolverOptions {
+      platform,
+      home,
+      env_files,
+      ignore_environment,
+      sandbox_root,
+      host,
+    }: &ResolverOptions,
+  ) -> Result<Self> {
+    let mut env = if *ignore_environment {
+      Environment::default()
+    } else {
+      Environment::process_vars()
+    };
+
+    for path in env_files {
+      env.vars.extend(Environment::parse_env_file(path)?);
+    }
+
+    // The host is only asked for the home directory when nothing else gives
+    // one, as it may not have one for the current user.
+    env.home = match (home, env.var("HOME")) {
+      (Some(home), _) => home.clone(),
+      (None, Some(home)) => PathBuf::from(home),
+      (None, None) if !ignore_environment => Environment::host_home()?,
+      (None, None) => PathBuf::new(),
+    };
+
+    if env.home.as_os_str().is_empty() {
+      return Err(Error::InvalidHomeDirectory);
+    }
+
+    // The uid of the host user would make a simulated platform's paths depend
+    // on the host, so it is only used when resolving for the host.
+    if platform.is_none() {
+      env.uid = Environment::host_uid();
+    }
+
+    // Windows is only asked for its Known Folders when nothing overrides the
+    // current user's environment.
+    let known_folders =
+      platform.is_none() && home.is_none() && env_files.is_empty() && !ignore_environment;
+    let platform = platform.clone().unwrap_or_default();
+    let mut resolver = Self::new(platform, env).detect_sandbox(sandbox_root);
+    resolver.host = *host;
+    resolver.known_folders = known_folders;
+    Ok(resolver)
+  }
+}
+
+impl Environment {
+  /// Reads `NAME=value` pairs, one per line. Blank lines, comments, and a
+  /// leading `export` are ignored, and values may be wrapped in quotes.
+  pub fn parse_env_file(path: &Path) -> Result<Vec<(String, OsString)>> {
+    let contents = fs::read_to_string(path).map_err(|error| Error::EnvironmentFile {
+      path: path.to_path_buf(),
+      error,
+    })?;
+
+    let vars = contents
+      .lines()
+      .map(str::trim)
+      .filter(|line| !line.is_empty() && !line.starts_with('#'))
+      .map(|line| line.strip_prefix("export ").unwrap_or(line))
+      .filter_map(|line| line.split_once('='))
+      .map(|(name, value)| {
+        let value = value.trim();
+        let value = match (value.chars().next(), value.chars().last()) {
+          (Some('"'), Some('"')) | (Some('\''), Some('\'')) if value.len() >= 2 => {
+            &value[1..value.len() - 1]
+          }
+          _ => value,
+        };
+        (name.trim().to_string(), OsString::from(value))
+      })
+      .collect();
+
+    Ok(vars)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::platform::testing::TempDir;
+
+  #[test]
+  fn env_files_skip_comments_and_malformed_lines() {
+    let directory = TempDir::new();
+    let path = directory.write(
+      "env",
+      "# a comment\n\nexport XDG_CACHE_HOME=/var/cache/alice\n  XDG_CONFIG_HOME = /etc/alice  \nnot an assignment\n",
+    );
+    let vars = Environment::parse_env_file(&path).unwrap();
+    assert_eq!(
+      vars,
+      vec![
+        ("XDG_CACHE_HOME".to_string(), "/var/cache/alice".into()),
+        ("XDG_CONFIG_HOME".to_string(), "/etc/alice".into()),
+      ]
+    );
+  }
+
+  #[test]
+  fn env_files_unquote_values() {
+    let directory = TempDir::new();
+    let path = directory.write(
+      "env",
+      "DOUBLE=\"/home/alice/My Files\"\nSINGLE='/home/alice/$HOME'\nMISMATCHED=\"/tmp'\nQUOTE=\"\n",
+    );
+    let vars = Environment::parse_env_file(&path).unwrap();
+    assert_eq!(
+      vars,
+      vec![
+        ("DOUBLE".to_string(), "/home/alice/My Files".into()),
+        ("SINGLE".to_string(), "/home/alice/$HOME".into()),
+        ("MISMATCHED".to_string(), "\"/tmp'".into()),
+        ("QUOTE".to_string(), "\"".into()),
+      ]
+    );
+  }
+
+  #[test]
+  fn missing_env_files_are_reported() {
+    let directory = TempDir::new();
+    let path = directory.path().join("missing");
+    assert!(matches!(
+      Environment::parse_env_file(&path),
+      Err(Error::EnvironmentFile { .. })
+    ));
+  }
+
+  #[test]
+  fn the_home_option_overrides_the_environment() {
+    let directory = TempDir::new();
+    let env_file = directory.write("env", "HOME=/home/alice\n");
+    let mut options = ResolverOptions {
+      platform: Some(Platform::Linux),
+      home: Some(PathBuf::from("/home/bob")),
+      env_files: vec![env_file],
+      ignore_environment: true,
+      sandbox_root: directory.path().to_path_buf(),
+      host: false,
+    };
+    let resolver = Resolver::try_from(&options).unwrap();
+    assert_eq!(resolver.env.home, PathBuf::from("/home/bob"));
+
+    options.home = None;
+    let resolver = Resolver::try_from(&options).unwrap();
+    assert_eq!(resolver.env.home, PathBuf::from("/home/alice"));
+
+    options.env_files.clear();
+    assert!(matches!(
+      Resolver::try_from(&options),
+      Err(Error::InvalidHomeDirectory)
+    ));
+  }
+}
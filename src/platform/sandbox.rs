@@ -0,0 +1,293 @@
+use super::{Environment, Platform, Source};
+use std::fs;
+use std::path::{Path, PathBuf};
+use strum::Display;
+
+/// An application sandbox that changes where an application's files live.
+#[derive(Debug, Clone, PartialEq, Eq, Display)]
+#[strum(serialize_all = "lowercase")]
+pub enum Sandbox {
+  Flatpak {
+    id: String,
+  },
+  Snap {
+    user_data: PathBuf,
+    user_common: Option<PathBuf>,
+    real_home: Option<PathBuf>,
+  },
+  AppImage {
+    home: Option<PathBuf>,
+    config: Option<PathBuf>,
+  },
+}
+
+impl Sandbox {
+  /// Detects a sandbox from the environment, and from marker files found
+  /// relative to `root` (normally `/`).
+  pub fn detect(env: &Environment, root: &Path) -> Option<Self> {
+    Self::flatpak(env, root)
+      .or_else(|| Self::snap(env))
+      .or_else(|| Self::appimage(env, root))
+  }
+
+  fn flatpak(env: &Environment, root: &Path) -> Option<Self> {
+    let id = env
+      .var("FLATPAK_ID")
+      .map(|id| id.to_string_lossy().into_owned())
+      .or_else(|| {
+        // The [Application] section of /.flatpak-info names the application.
+        let info = fs::read_to_string(root.join(".flatpak-info")).ok()?;
+        info
+          .lines()
+          .skip_while(|line| line.trim() != "[Application]")
+          .skip(1)
+          .take_while(|line| !line.trim_start().starts_with('['))
+          .filter_map(|line| line.split_once('='))
+          .find(|(key, _)| key.trim() == "name")
+          .map(|(_, value)| value.trim().to_string())
+      })?;
+
+    Some(Self::Flatpak { id })
+  }
+
+  fn snap(env: &Environment) -> Option<Self> {
+    env.var("SNAP")?;
+    let user_data = PathBuf::from(env.var("SNAP_USER_DATA")?);
+    let user_common = env.var("SNAP_USER_COMMON").map(PathBuf::from);
+    let real_home = env.var("SNAP_REAL_HOME").map(PathBuf::from);
+
+    Some(Self::Snap {
+      user_data,
+      user_common,
+      real_home,
+    })
+  }
+
+  /// AppImages are not sandboxed, but support a "portable" mode where the
+  /// home and config directories live next to the AppImage itself.
+  fn appimage(env: &Environment, root: &Path) -> Option<Self> {
+    let appimage = env.var("APPIMAGE")?.to_string_lossy().into_owned();
+    let portable = |suffix: &str| {
+      let path = format!("{appimage}.{suffix}");
+      root
+        .join(path.trim_start_matches('/'))
+        .is_dir()
+        .then(|| PathBuf::from(path))
+    };
+
+    Some(Self::AppImage {
+      home: portable("home"),
+      config: portable("config"),
+    })
+  }
+
+  pub(crate) fn source(&self) -> Source {
+    Source::Sandbox {
+      sandbox: self.to_string(),
+    }
+  }
+
+  /// The home directory as seen from inside the sandbox.
+  pub(crate) fn home(&self) -> Option<&Path> {
+    match self {
+      Self::Snap { user_data, .. } => Some(user_data),
+      Self::AppImage { home, .. } => home.as_deref(),
+      Self::Flatpak { .. } => None,
+    }
+  }
+
+  /// The home directory as seen from the host.
+  pub(crate) fn host_home(&self) -> Option<&Path> {
+    match self {
+      Self::Snap { real_home, .. } => real_home.as_deref(),
+      _ => None,
+    }
+  }
+
+  /// Flatpak redirects the XDG base directories into a per-application
+  /// directory, and exports the original values as `HOST_XDG_*`. Snap copies
+  /// `SNAP_USER_DATA` for every revision, so the cache is kept in
+  /// `SNAP_USER_COMMON`, which is shared by every revision.
+  pub(crate) fn xdg_home(&self, name: &str, home: &Path) -> Option<PathBuf> {
+    match self {
+      Self::Flatpak { id } => {
+        let directory = match name {
+          "XDG_CACHE_HOME" => "cache",
+          "XDG_CONFIG_HOME" => "config",
+          "XDG_DATA_HOME" => "data",
+          "XDG_STATE_HOME" => ".local/state",
+          _ => return None,
+        };
+        let mut components = vec![".var", "app", id.as_str()];
+        components.extend(directory.split('/'));
+        Some(Platform::Linux.join(home, &components))
+      }
+      Self::Snap {
+        user_common: Some(user_common),
+        ..
+      } if name == "XDG_CACHE_HOME" => Some(Platform::Linux.join(user_common, &[".cache"])),
+      _ => None,
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::platform::testing::TempDir;
+  use crate::platform::{Base, Resolver};
+
+  fn resolver(env: Environment, root: &Path) -> Resolver {
+    Resolver::new(Platform::Linux, env).detect_sandbox(root)
+  }
+
+  fn cache(resolver: &Resolver) -> PathBuf {
+    Base::Cache.resolve(resolver).unwrap()
+  }
+
+  #[test]
+  fn nothing_is_detected_without_markers() {
+    let root = TempDir::new();
+    let resolver = resolver(Environment::new("/home/alice"), root.path());
+    assert_eq!(resolver.sandbox, None);
+    assert_eq!(cache(&resolver), PathBuf::from("/home/alice/.cache"));
+  }
+
+  #[test]
+  fn flatpak_is_detected_from_the_environment() {
+    let root = TempDir::new();
+    let env = Environment::new("/home/alice").with_var("FLATPAK_ID", "org.example.Nifty");
+    let resolver = resolver(env, root.path());
+    assert_eq!(
+      resolver.sandbox,
+      Some(Sandbox::Flatpak {
+        id: "org.example.Nifty".to_string()
+      })
+    );
+    assert_eq!(
+      cache(&resolver),
+      PathBuf::from("/home/alice/.var/app/org.example.Nifty/cache")
+    );
+  }
+
+  #[test]
+  fn flatpak_is_detected_from_the_info_file() {
+    let root = TempDir::new();
+    root.write(
+      ".flatpak-info",
+      "[Instance]\nname=wrong\n\n[Application]\nname=org.example.Nifty\nruntime=runtime/org.gnome.Platform\n",
+    );
+    let resolver = resolver(Environment::new("/home/alice"), root.path());
+    assert_eq!(
+      resolver.sandbox,
+      Some(Sandbox::Flatpak {
+        id: "org.example.Nifty".to_string()
+      })
+    );
+  }
+
+  #[test]
+  fn snap_redirects_the_home_directory() {
+    let root = TempDir::new();
+    let env = Environment::new("/home/alice/snap/nifty/42")
+      .with_var("SNAP", "/snap/nifty/42")
+      .with_var("SNAP_USER_DATA", "/home/alice/snap/nifty/42")
+      .with_var("SNAP_REAL_HOME", "/home/alice");
+    let resolver = resolver(env, root.path());
+    assert_eq!(
+      resolver.sandbox,
+      Some(Sandbox::Snap {
+        user_data: PathBuf::from("/home/alice/snap/nifty/42"),
+        user_common: None,
+        real_home: Some(PathBuf::from("/home/alice")),
+      })
+    );
+    assert_eq!(
+      cache(&resolver),
+      PathBuf::from("/home/alice/snap/nifty/42/.cache")
+    );
+    assert_eq!(
+      Base::Home.explain(&resolver).unwrap().source,
+      Source::Sandbox {
+        sandbox: "snap".to_string()
+      }
+    );
+  }
+
+  #[test]
+  fn snap_keeps_the_cache_across_revisions() {
+    let root = TempDir::new();
+    let env = Environment::new("/home/alice/snap/nifty/42")
+      .with_var("SNAP", "/snap/nifty/42")
+      .with_var("SNAP_USER_DATA", "/home/alice/snap/nifty/42")
+      .with_var("SNAP_USER_COMMON", "/home/alice/snap/nifty/common")
+      .with_var("SNAP_REAL_HOME", "/home/alice");
+    let resolver = resolver(env, root.path());
+    assert_eq!(
+      cache(&resolver),
+      PathBuf::from("/home/alice/snap/nifty/common/.cache")
+    );
+    assert_eq!(
+      Base::Config.resolve(&resolver).unwrap(),
+      PathBuf::from("/home/alice/snap/nifty/42/.config")
+    );
+  }
+
+  #[test]
+  fn appimage_uses_portable_directories() {
+    let root = TempDir::new();
+    fs::create_dir_all(root.path().join("apps/Nifty.AppImage.home")).unwrap();
+    fs::create_dir_all(root.path().join("apps/Nifty.AppImage.config")).unwrap();
+    let env = Environment::new("/home/alice").with_var("APPIMAGE", "/apps/Nifty.AppImage");
+    let resolver = resolver(env, root.path());
+    assert_eq!(
+      resolver.sandbox,
+      Some(Sandbox::AppImage {
+        home: Some(PathBuf::from("/apps/Nifty.AppImage.home")),
+        config: Some(PathBuf::from("/apps/Nifty.AppImage.config")),
+      })
+    );
+    assert_eq!(
+      Base::Config.resolve(&resolver).unwrap(),
+      PathBuf::from("/apps/Nifty.AppImage.config")
+    );
+    assert_eq!(
+      cache(&resolver),
+      PathBuf::from("/apps/Nifty.AppImage.home/.cache")
+    );
+  }
+
+  #[test]
+  fn appimage_without_portable_directories_uses_the_home_directory() {
+    let root = TempDir::new();
+    let env = Environment::new("/home/alice").with_var("APPIMAGE", "/apps/Nifty.AppImage");
+    let resolver = resolver(env, root.path());
+    assert_eq!(
+      resolver.sandbox,
+      Some(Sandbox::AppImage {
+        home: None,
+        config: None
+      })
+    );
+    assert_eq!(cache(&resolver), PathBuf::from("/home/alice/.cache"));
+  }
+
+  #[test]
+  fn host_paths_are_resolved_outside_the_sandbox() {
+    let root = TempDir::new();
+    let env = Environment::new("/home/alice")
+      .with_var("FLATPAK_ID", "org.example.Nifty")
+      .with_var("HOST_XDG_CACHE_HOME", "/var/cache/alice");
+    let mut flatpak = resolver(env, root.path());
+    flatpak.host = true;
+    assert_eq!(cache(&flatpak), PathBuf::from("/var/cache/alice"));
+
+    let env = Environment::new("/home/alice/snap/nifty/42")
+      .with_var("SNAP", "/snap/nifty/42")
+      .with_var("SNAP_USER_DATA", "/home/alice/snap/nifty/42")
+      .with_var("SNAP_REAL_HOME", "/home/alice");
+    let mut snap = resolver(env, root.path());
+    snap.host = true;
+    assert_eq!(cache(&snap), PathBuf::from("/home/alice/.cache"));
+  }
+}
@@ -0,0 +1,217 @@
+use super::{secure_temp, Fallback, Platform, Project, ProjectOptions, Resolved, Resolver};
+use crate::output::Format;
+use crate::{Error, Result};
+use std::path::PathBuf;
+
+/// A Unix domain socket path that fits in `sun_path`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Socket {
+  pub path: Resolved,
+  /// The size of `sun_path` on the platform, including the terminating NUL.
+  pub limit: usize,
+  /// The path in the runtime directory, if it was too long and a hashed path
+  /// was used instead.
+  pub original: Option<PathBuf>,
+}
+
+impl Platform {
+  /// The size of `sun_path` in `struct sockaddr_un`.
+  pub(crate) fn socket_path_limit(&self) -> Option<usize> {
+    match self {
+      Self::Linux | Self::Windows => Some(108),
+      Self::MacOS => Some(104),
+      Self::Wasm => None,
+    }
+  }
+}
+
+/// Composes the path of a socket named `name` in the project's runtime
+/// directory. If that path does not fit in `sun_path`, a path named after its
+/// hash is used in the `secure-temp` directory instead, so that every process
+/// computing the path for the same socket agrees on it.
+pub fn socket(
+  resolver: &Resolver,
+  options: &ProjectOptions,
+  name: &str,
+  fallback: &Fallback,
+) -> Result<Socket> {
+  // The name must stay inside the runtime directory.
+  let separator = |c: char| c == '/' || c == '\\' || c == '\0';
+  if matches!(name, "" | "." | "..") || name.contains(separator) {
+    return Err(Error::InvalidRequest(format!(
+      "socket name must be a single path component: {name}"
+    )));
+  }
+
+  let platform = &resolver.platform;
+  let limit = platform
+    .socket_path_limit()
+    .ok_or(Error::NotDefinedByPlatformStandard)?;
+  let fits = |path: &PathBuf| path.as_os_str().len() < limit;
+
+  let path = Project::Runtime
+    .explain_with(resolver, options, fallback)?
+    .join(platform, &[name]);
+  if fits(&path.path) {
+    return Ok(Socket {
+      path,
+      limit,
+      original: None,
+    });
+  }
+
+  let hash = format!("{:016x}", fnv1a(path.path.to_string_lossy().as_bytes()));
+  let short = secure_temp(resolver)?.join(platform, &[hash]);
+  match fits(&short.path) {
+    true => Ok(Socket {
+      path: short,
+      limit,
+      original: Some(path.path),
+    }),
+    false => Err(Error::SocketPathTooLong {
+      path: short.path,
+      limit,
+    }),
+  }
+}
+
+/// The 64-bit FNV-1a hash, which is stable across versions and platforms.
+fn fnv1a(bytes: &[u8]) -> u64 {
+  bytes.iter().fold(0xcbf29ce484222325, |hash, byte| {
+    (hash ^ u64::from(*byte)).wrapping_mul(0x100000001b3)
+  })
+}
+
+#[derive(Debug)]
+#[cfg_attr(feature = "serde1", derive(serde::Serialize, serde::Deserialize))]
+pub(crate) struct StructuredSocket {
+  pub(crate) path: String,
+  pub(crate) limit: usize,
+  #[cfg_attr(
+    feature = "serde1",
+    serde(default, skip_serializing_if = "Option::is_none")
+  )]
+  pub(crate) original: Option<String>,
+  #[cfg_attr(
+    feature = "serde1",
+    serde(default, skip_serializing_if = "Option::is_none")
+  )]
+  pub(crate) fallback: Option<String>,
+}
+
+impl StructuredSocket {
+  pub(crate) fn render(&self, format: &Format, explain: bool) -> Result<String> {
+    format.render(self, || {
+      let mut lines = vec![self.path.clone()];
+      if explain {
+        lines.push(format!("limit: {} bytes", self.limit));
+        lines.extend(self.original.iter().map(|path| format!("original: {path}")));
+        lines.extend(
+          self
+            .fallback
+            .iter()
+            .map(|fallback| format!("fallback: {fallback}")),
+        );
+      }
+      Ok(lines.join("\n"))
+    })
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  #[cfg(target_os = "linux")]
+  use crate::platform::account::uid;
+  use crate::platform::testing::{project_options, TempDir};
+  use crate::platform::Environment;
+
+  #[test]
+  fn sockets_are_kept_in_the_runtime_directory() {
+    let env = Environment::new("/home/alice").with_var("XDG_RUNTIME_DIR", "/run/user/1000");
+    let resolver = Resolver::new(Platform::Linux, env);
+    let socket = socket(
+      &resolver,
+      &project_options("Nifty"),
+      "api.sock",
+      &Fallback::Strict,
+    )
+    .unwrap();
+    assert_eq!(
+      socket.path.path,
+      PathBuf::from("/run/user/1000/nifty/api.sock")
+    );
+    assert_eq!(socket.limit, 108);
+    assert_eq!(socket.original, None);
+  }
+
+  #[test]
+  fn names_must_be_a_single_component() {
+    let env = Environment::new("/home/alice").with_var("XDG_RUNTIME_DIR", "/run/user/1000");
+    let resolver = Resolver::new(Platform::Linux, env);
+    for name in ["", ".", "..", "../../etc/x", "a/b", r"a\b"] {
+      assert!(
+        matches!(
+          socket(
+            &resolver,
+            &project_options("Nifty"),
+            name,
+            &Fallback::Strict
+          ),
+          Err(Error::InvalidRequest(_))
+        ),
+        "{name}"
+      );
+    }
+  }
+
+  #[test]
+  #[cfg(target_os = "linux")]
+  fn long_paths_are_hashed_into_the_secure_temp_directory() {
+    let temp = TempDir::new();
+    let runtime = format!("/run/{}", "x".repeat(100));
+    let env = Environment::new("/home/alice")
+      .with_var("XDG_RUNTIME_DIR", runtime.as_str())
+      .with_var("TMPDIR", temp.path())
+      .with_uid(uid());
+    let resolver = Resolver::new(Platform::default(), env);
+    let socket = socket(
+      &resolver,
+      &project_options("Nifty"),
+      "api.sock",
+      &Fallback::Strict,
+    )
+    .unwrap();
+    let original = PathBuf::from(format!("{runtime}/nifty/api.sock"));
+    let hash = format!("{:016x}", fnv1a(original.to_string_lossy().as_bytes()));
+    assert_eq!(
+      socket.path.path,
+      temp.path().join(format!("runtime-{}", uid())).join(hash)
+    );
+    assert_eq!(socket.original, Some(original));
+  }
+
+  #[test]
+  #[cfg(target_os = "linux")]
+  fn hashed_paths_do_not_depend_on_the_user_name() {
+    let temp = TempDir::new();
+    let runtime = format!("/run/{}", "x".repeat(100));
+    let env = Environment::new("/home/alice")
+      .with_var("XDG_RUNTIME_DIR", runtime.as_str())
+      .with_var("TMPDIR", temp.path())
+      .with_uid(uid());
+    let path = |env: Environment| {
+      let resolver = Resolver::new(Platform::default(), env);
+      socket(
+        &resolver,
+        &project_options("Nifty"),
+        "api.sock",
+        &Fallback::Strict,
+      )
+      .unwrap()
+      .path
+      .path
+    };
+    assert_eq!(path(env.clone()), path(env.with_var("USER", "alice")));
+  }
+}
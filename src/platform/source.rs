@@ -0,0 +1,127 @@
+use super::Platform;
+use std::ffi::OsStr;
+use std::fmt;
+use std::path::PathBuf;
+
+/// The input that decided where a path resolved to.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(
+  feature = "serde1",
+  derive(serde::Serialize, serde::Deserialize),
+  serde(tag = "type", rename_all = "kebab-case")
+)]
+pub enum Source {
+  /// An environment variable, such as `XDG_CACHE_HOME`.
+  Variable { name: String, value: String },
+  /// A line of a configuration file, such as `user-dirs.dirs`.
+  File { path: PathBuf, line: usize },
+  /// The default defined by the platform standard.
+  Default,
+  /// The home directory given to the resolver.
+  Home,
+  /// A Windows Known Folder, as reported by Windows.
+  KnownFolder { name: String },
+  /// A sandbox that redirects the directory elsewhere.
+  Sandbox { sandbox: String },
+  /// The mount point of a file, for paths that depend on the file's volume.
+  MountPoint { path: PathBuf },
+  /// A directory that was found on disk.
+  Directory { path: PathBuf },
+  /// The project options, for paths made only of the project's name.
+  Project,
+  /// The alternate path given with `--default`.
+  Fallback,
+}
+
+impl Source {
+  pub(crate) fn variable(name: &str, value: &OsStr) -> Self {
+    Self::Variable {
+      name: name.to_string(),
+      value: value.to_string_lossy().into_owned(),
+    }
+  }
+}
+
+impl fmt::Display for Source {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      Self::Variable { name, value } => write!(f, "environment variable {name}={value}"),
+      Self::File { path, line } => write!(f, "line {line} of {}", path.display()),
+      Self::Default => write!(f, "default of the platform standard"),
+      Self::Home => write!(f, "home directory"),
+      Self::KnownFolder { name } => write!(f, "Windows Known Folder FOLDERID_{name}"),
+      Self::Sandbox { sandbox } => write!(f, "{sandbox} sandbox"),
+      Self::MountPoint { path } => write!(f, "mount point {}", path.display()),
+      Self::Directory { path } => write!(f, "directory {}", path.display()),
+      Self::Project => write!(f, "project options"),
+      Self::Fallback => write!(f, "--default option"),
+    }
+  }
+}
+
+/// An input that was considered, but ignored.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde1", derive(serde::Serialize, serde::Deserialize))]
+pub struct Rejected {
+  #[cfg_attr(feature = "serde1", serde(flatten))]
+  pub source: Source,
+  pub reason: String,
+}
+
+impl Rejected {
+  pub(crate) fn new<S: Into<String>>(source: Source, reason: S) -> Self {
+    Self {
+      source,
+      reason: reason.into(),
+    }
+  }
+}
+
+impl fmt::Display for Rejected {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "{} ({})", self.source, self.reason)
+  }
+}
+
+/// A resolved path, along with the inputs that explain it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Resolved<T = PathBuf> {
+  pub path: T,
+  pub source: Source,
+  pub rejected: Vec<Rejected>,
+  /// The fallback that was used, if the requested path is not defined.
+  pub fallback: Option<String>,
+}
+
+impl<T> Resolved<T> {
+  pub fn new(path: T, source: Source) -> Self {
+    Self {
+      path,
+      source,
+      rejected: Vec::new(),
+      fallback: None,
+    }
+  }
+
+  pub(crate) fn rejecting(mut self, rejected: Vec<Rejected>) -> Self {
+    self.rejected.splice(0..0, rejected);
+    self
+  }
+}
+
+impl Resolved {
+  /// Joins path components, keeping the input that decided the base path.
+  pub(crate) fn join<S: AsRef<OsStr>>(mut self, platform: &Platform, components: &[S]) -> Self {
+    self.path = platform.join(&self.path, components);
+    self
+  }
+
+  pub fn listed(self) -> Resolved<Vec<PathBuf>> {
+    Resolved {
+      path: vec![self.path],
+      source: self.source,
+      rejected: self.rejected,
+      fallback: self.fallback,
+    }
+  }
+}
@@ -1,6 +1,8 @@
+use super::{Attributes, Scope};
+use std::collections::BTreeMap;
 use strum::{Display, EnumIter, EnumString, EnumVariantNames};
 
-#[derive(Debug, PartialEq, Eq, Hash, Display, EnumString, EnumIter, EnumVariantNames)]
+#[derive(Debug, Default, PartialEq, Eq, Hash, Display, EnumString, EnumIter, EnumVariantNames)]
 #[strum(serialize_all = "lowercase")]
 #[cfg_attr(
   feature = "serde1",
@@ -10,6 +12,7 @@ use strum::{Display, EnumIter, EnumString, EnumVariantNames};
 pub(crate) enum Status {
   Supported,
   Unsupported,
+  #[default]
   Unknown,
 }
 
@@ -17,17 +20,26 @@ pub(crate) enum Status {
 #[cfg_attr(feature = "serde1", derive(serde::Serialize, serde::Deserialize))]
 pub(crate) struct StructuredStatus {
   pub(crate) status: Status,
+  /// The status of project paths for every scope, as `status` only covers
+  /// the user scope.
+  #[cfg_attr(
+    feature = "serde1",
+    serde(default, skip_serializing_if = "BTreeMap::is_empty")
+  )]
+  pub(crate) scopes: BTreeMap<Scope, Status>,
+  /// How the platform treats the path, which for project paths describes
+  /// the user scope.
+  #[cfg_attr(feature = "serde1", serde(default))]
+  pub(crate) attributes: Attributes,
 }
 
 impl From<Status> for StructuredStatus {
   fn from(status: Status) -> Self {
-    Self { status }
-  }
-}
-
-impl Default for Status {
-  fn default() -> Self {
-    Self::Unknown
+    Self {
+      status,
+      scopes: BTreeMap::new(),
+      attributes: Attributes::default(),
+    }
   }
 }
 
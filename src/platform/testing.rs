@@ -0,0 +1,51 @@
+use super::ProjectOptions;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// A directory under the system's temporary directory, removed when dropped.
+pub(crate) struct TempDir(PathBuf);
+
+impl TempDir {
+  pub(crate) fn new() -> Self {
+    static COUNT: AtomicUsize = AtomicUsize::new(0);
+    let name = format!(
+      "platform-path-{}-{}",
+      std::process::id(),
+      COUNT.fetch_add(1, Ordering::SeqCst)
+    );
+    let path = std::env::temp_dir().join(name);
+    fs::create_dir_all(&path).expect("create temporary directory");
+    Self(path)
+  }
+
+  pub(crate) fn path(&self) -> &Path {
+    &self.0
+  }
+
+  /// Writes a file relative to the directory, creating its parents.
+  pub(crate) fn write(&self, relative: &str, contents: &str) -> PathBuf {
+    let path = self.0.join(relative);
+    if let Some(parent) = path.parent() {
+      fs::create_dir_all(parent).expect("create parent directory");
+    }
+    fs::write(&path, contents).expect("write file");
+    path
+  }
+}
+
+/// The options of a project with only an application name, for the user.
+pub(crate) fn project_options(application: &str) -> ProjectOptions {
+  ProjectOptions {
+    qualifier: None,
+    organization: None,
+    application: application.to_string(),
+    scope: Default::default(),
+  }
+}
+
+impl Drop for TempDir {
+  fn drop(&mut self) {
+    let _ = fs::remove_dir_all(&self.0);
+  }
+}
@@ -0,0 +1,222 @@
+use super::{Base, Platform, Resolved, Resolver};
+use crate::{Error, Result};
+use std::path::Path;
+
+/// The trash directory that a file would be moved to. On Linux, files on
+/// other volumes than the home trash are trashed on their own volume, as
+/// described by the FreeDesktop.org Trash specification.
+pub fn trash_for(resolver: &Resolver, file: &Path) -> Result<Resolved> {
+  let home = Base::Trash.explain(resolver)?;
+  if resolver.platform != Platform::default() {
+    return Err(Error::SimulatedPlatform(resolver.platform.clone()));
+  }
+
+  match resolver.platform {
+    Platform::Linux => volume_trash(home, file),
+    _ => Ok(home),
+  }
+}
+
+#[cfg(unix)]
+fn volume_trash(home: Resolved, file: &Path) -> Result<Resolved> {
+  use super::account::uid;
+  use std::fs;
+  use std::os::unix::fs::MetadataExt;
+
+  let file = existing(file)?;
+  let device = fs::metadata(&file)?.dev();
+  if fs::metadata(existing(&home.path)?)?.dev() == device {
+    return Ok(home);
+  }
+
+  let topdir = file
+    .ancestors()
+    .take_while(|path| {
+      fs::metadata(path)
+        .map(|metadata| metadata.dev() == device)
+        .unwrap_or(false)
+    })
+    .last()
+    .unwrap_or(&file);
+  topdir_trash(topdir, uid())
+}
+
+/// The trash directory of `uid` at the top directory of a volume.
+#[cfg(unix)]
+fn topdir_trash(topdir: &Path, uid: u32) -> Result<Resolved> {
+  use super::{Rejected, Source};
+  use std::fs;
+  use std::os::unix::fs::MetadataExt;
+
+  let source = Source::MountPoint {
+    path: topdir.to_path_buf(),
+  };
+  let reject = |path: &Path, reason: &str| {
+    let source = Source::Directory {
+      path: path.to_path_buf(),
+    };
+    Some(Rejected::new(source, reason))
+  };
+
+  // $topdir/.Trash is shared by every user, and must be a real directory
+  // with the sticky bit set. Otherwise, it must not be used, and neither may
+  // a $topdir/.Trash/$uid that fails the checks, but $topdir/.Trash-$uid
+  // still may be.
+  let shared = topdir.join(".Trash");
+  let rejected = match fs::symlink_metadata(&shared) {
+    Err(_) => None,
+    Ok(metadata) if metadata.file_type().is_symlink() => reject(&shared, "is a symbolic link"),
+    Ok(metadata) if !metadata.is_dir() => reject(&shared, "is not a directory"),
+    Ok(metadata) if metadata.mode() & 0o1000 == 0 => {
+      reject(&shared, "does not have the sticky bit set")
+    }
+    Ok(_) => {
+      let path = shared.join(uid.to_string());
+      match verify(&path, uid) {
+        Ok(()) => return Ok(Resolved::new(path, source)),
+        Err(Error::UnsafeDirectory { path, reason }) => reject(&path, &reason),
+        Err(error) => return Err(error),
+      }
+    }
+  };
+
+  let path = topdir.join(format!(".Trash-{uid}"));
+  verify(&path, uid)?;
+  Ok(Resolved::new(path, source).rejecting(rejected.into_iter().collect()))
+}
+
+#[cfg(not(unix))]
+fn volume_trash(home: Resolved, _file: &Path) -> Result<Resolved> {
+  Ok(home)
+}
+
+/// The file itself, or its closest existing ancestor. Symbolic links are
+/// trashed themselves, so the directory containing a link is used instead.
+#[cfg(unix)]
+fn existing(path: &Path) -> Result<std::path::PathBuf> {
+  use std::fs;
+
+  let path = std::env::current_dir()?.join(path);
+  let path = match fs::symlink_metadata(&path) {
+    Ok(metadata) if metadata.file_type().is_symlink() => path.parent().unwrap_or(&path),
+    _ => &path,
+  };
+
+  Ok(
+    path
+      .ancestors()
+      .find_map(|path| fs::canonicalize(path).ok())
+      .unwrap_or_else(|| "/".into()),
+  )
+}
+
+/// A trash directory that already exists must be a real directory owned by
+/// the current user.
+#[cfg(unix)]
+fn verify(path: &Path, uid: u32) -> Result<()> {
+  use std::fs;
+  use std::os::unix::fs::MetadataExt;
+
+  let metadata = match fs::symlink_metadata(path) {
+    Ok(metadata) => metadata,
+    Err(_) => return Ok(()),
+  };
+
+  let reason = if metadata.file_type().is_symlink() {
+    "path is a symbolic link".to_string()
+  } else if !metadata.is_dir() {
+    "path is not a directory".to_string()
+  } else if metadata.uid() != uid {
+    let owner = metadata.uid();
+    format!("owned by uid {owner}, not {uid}")
+  } else {
+    return Ok(());
+  };
+
+  Err(Error::UnsafeDirectory {
+    path: path.to_path_buf(),
+    reason,
+  })
+}
+
+#[cfg(test)]
+#[cfg(unix)]
+mod tests {
+  use super::*;
+  use crate::platform::testing::TempDir;
+  use crate::platform::{Rejected, Source};
+  use std::fs;
+  use std::os::unix::fs::{symlink, PermissionsExt};
+  use std::path::PathBuf;
+
+  const UID: u32 = 1000;
+
+  fn shared(topdir: &Path, mode: u32) -> PathBuf {
+    let shared = topdir.join(".Trash");
+    fs::create_dir(&shared).unwrap();
+    fs::set_permissions(&shared, fs::Permissions::from_mode(mode)).unwrap();
+    shared
+  }
+
+  fn rejected(path: PathBuf, reason: &str) -> Vec<Rejected> {
+    vec![Rejected::new(Source::Directory { path }, reason)]
+  }
+
+  #[test]
+  fn a_sticky_shared_trash_is_used() {
+    let temp = TempDir::new();
+    let shared = shared(temp.path(), 0o1777);
+    let resolved = topdir_trash(temp.path(), UID).unwrap();
+    assert_eq!(resolved.path, shared.join("1000"));
+    assert_eq!(
+      resolved.source,
+      Source::MountPoint {
+        path: temp.path().to_path_buf()
+      }
+    );
+    assert!(resolved.rejected.is_empty());
+  }
+
+  #[test]
+  fn a_missing_shared_trash_is_skipped() {
+    let temp = TempDir::new();
+    let resolved = topdir_trash(temp.path(), UID).unwrap();
+    assert_eq!(resolved.path, temp.path().join(".Trash-1000"));
+    assert!(resolved.rejected.is_empty());
+  }
+
+  #[test]
+  fn a_shared_trash_without_the_sticky_bit_is_rejected() {
+    let temp = TempDir::new();
+    let shared = shared(temp.path(), 0o777);
+    let resolved = topdir_trash(temp.path(), UID).unwrap();
+    assert_eq!(resolved.path, temp.path().join(".Trash-1000"));
+    assert_eq!(
+      resolved.rejected,
+      rejected(shared, "does not have the sticky bit set")
+    );
+  }
+
+  #[test]
+  fn a_symlinked_shared_trash_is_rejected() {
+    let temp = TempDir::new();
+    let shared = shared(temp.path(), 0o1777);
+    let target = temp.path().join("elsewhere");
+    fs::rename(&shared, &target).unwrap();
+    symlink(&target, &shared).unwrap();
+    let resolved = topdir_trash(temp.path(), UID).unwrap();
+    assert_eq!(resolved.path, temp.path().join(".Trash-1000"));
+    assert_eq!(resolved.rejected, rejected(shared, "is a symbolic link"));
+  }
+
+  #[test]
+  fn an_unsafe_user_trash_falls_back_to_the_topdir() {
+    let temp = TempDir::new();
+    let shared = shared(temp.path(), 0o1777);
+    let user = shared.join("1000");
+    symlink(temp.path(), &user).unwrap();
+    let resolved = topdir_trash(temp.path(), UID).unwrap();
+    assert_eq!(resolved.path, temp.path().join(".Trash-1000"));
+    assert_eq!(resolved.rejected, rejected(user, "path is a symbolic link"));
+  }
+}
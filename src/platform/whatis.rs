@@ -0,0 +1,388 @@
+use super::{Base, Platform, Project, ProjectOptions, Resolver, Scope, User};
+use crate::output::Format;
+use crate::Result;
+use std::path::{Path, PathBuf};
+use strum::IntoEnumIterator;
+
+/// The standard directories that a path lives in.
+#[derive(Debug, Clone, Default)]
+pub struct Classification {
+  /// The most specific base directories containing the path. There is more
+  /// than one when several resolve to the same directory, such as `config`
+  /// and `preference` on Linux.
+  pub base: Vec<Base>,
+  /// The most specific user directories containing the path.
+  pub user: Vec<User>,
+  /// The project guessed from the path, if it is inside a base directory
+  /// that holds project directories.
+  pub project: Option<String>,
+  /// The project directories containing the path.
+  pub project_paths: Vec<Project>,
+  /// The rest of the path, relative to the most specific directory.
+  pub relative: Option<String>,
+}
+
+impl Platform {
+  /// Strips `base` from the start of `path`, comparing whole components, and
+  /// ignoring case on Windows.
+  pub(crate) fn strip_prefix(&self, path: &Path, base: &Path) -> Option<String> {
+    let separator = self.separator();
+    let path = path.to_string_lossy();
+    let base = base.to_string_lossy();
+    let base = base.trim_end_matches(separator);
+    let prefix = path.get(..base.len())?;
+    let matched = match self {
+      Self::Windows => prefix.eq_ignore_ascii_case(base),
+      _ => prefix == base,
+    };
+    if !matched {
+      return None;
+    }
+    match path[base.len()..].strip_prefix(separator) {
+      Some(rest) => Some(rest.trim_end_matches(separator).to_string()),
+      None if path.len() == base.len() => Some(String::new()),
+      None => None,
+    }
+  }
+}
+
+impl Platform {
+  /// Removes `.` components, and `..` components along with the component
+  /// they cancel, without consulting the file system, so that a path cannot
+  /// appear to be inside a directory it escapes from. A `..` never climbs
+  /// above the root or a Windows drive.
+  pub(crate) fn normalize(&self, path: &Path) -> PathBuf {
+    let separator = self.separator();
+    let is_separator = |c: char| c == separator || (*self == Self::Windows && c == '/');
+    let path = path.to_string_lossy();
+    let rest = path.trim_start_matches(is_separator);
+    let mut root = match &path[..path.len() - rest.len()] {
+      // Only Windows gives a meaning to a doubled separator, for UNC paths.
+      root if *self != Self::Windows && !root.is_empty() => separator.to_string(),
+      root => root.to_string(),
+    };
+    let mut parts = rest.split(is_separator).peekable();
+    if *self == Self::Windows {
+      if let Some(drive) = parts.next_if(|part| part.ends_with(':')) {
+        root = format!("{drive}{separator}");
+      }
+    }
+
+    let mut components: Vec<&str> = Vec::new();
+    for part in parts {
+      match part {
+        "" | "." => (),
+        ".." => match components.last() {
+          Some(&"..") | None if root.is_empty() => components.push(part),
+          Some(_) => {
+            components.pop();
+          }
+          None => (),
+        },
+        part => components.push(part),
+      }
+    }
+
+    PathBuf::from(root + &components.join(&separator.to_string()))
+  }
+}
+
+/// Finds the standard directories that `path` lives in. Only the most
+/// specific directories are reported, so a file in the cache directory is
+/// not also reported as being in the home directory.
+pub fn whatis(resolver: &Resolver, path: &Path) -> Classification {
+  let platform = &resolver.platform;
+  let path = &platform.normalize(path);
+  let mut classification = Classification::default();
+
+  // The length of the relative path is the inverse of how specific the
+  // containing directory is.
+  let mut shortest: Option<String> = None;
+  let mut closer = |relative: &String| match &shortest {
+    Some(shortest) if shortest.len() < relative.len() => None,
+    Some(shortest) if shortest.len() == relative.len() => Some(false),
+    _ => {
+      shortest = Some(relative.clone());
+      Some(true)
+    }
+  };
+
+  for base in Base::iter() {
+    let Ok(directory) = base.resolve(resolver) else {
+      continue;
+    };
+    let Some(relative) = platform.strip_prefix(path, &directory) else {
+      continue;
+    };
+    match closer(&relative) {
+      Some(true) => classification.base = vec![base],
+      Some(false) => classification.base.push(base),
+      None => (),
+    }
+  }
+
+  for user in User::iter() {
+    let Ok(directory) = user.resolve(resolver) else {
+      continue;
+    };
+    let Some(relative) = platform.strip_prefix(path, &directory) else {
+      continue;
+    };
+    match closer(&relative) {
+      Some(true) => {
+        classification.base.clear();
+        classification.user = vec![user];
+      }
+      Some(false) => classification.user.push(user),
+      None => (),
+    }
+  }
+
+  classification.relative = shortest;
+  if let Some((project, paths, relative)) = guess_project(resolver, path, &classification) {
+    // Base directories that share a path are narrowed down to those whose
+    // project directory contains the path.
+    let bases: Vec<Base> = classification
+      .base
+      .iter()
+      .copied()
+      .filter(|base| {
+        base
+          .project()
+          .is_some_and(|project| paths.contains(&project))
+      })
+      .collect();
+    if !bases.is_empty() {
+      classification.base = bases;
+    }
+    classification.project = Some(project);
+    classification.project_paths = paths;
+    classification.relative = Some(relative);
+  }
+
+  classification
+}
+
+/// Guesses the project from the components that follow the base directory.
+/// Project directories resolved for the guess are preferred, as they include
+/// any platform-specific subdirectory, but projects that do not follow the
+/// platform's naming conventions are still recognized by their first
+/// component.
+fn guess_project(
+  resolver: &Resolver,
+  path: &Path,
+  classification: &Classification,
+) -> Option<(String, Vec<Project>, String)> {
+  let platform = &resolver.platform;
+  let separator = platform.separator();
+  let projects: Vec<Project> = classification
+    .base
+    .iter()
+    .filter_map(Base::project)
+    .collect();
+  let relative = classification.relative.as_deref()?;
+  if projects.is_empty() || relative.is_empty() {
+    return None;
+  }
+
+  let components: Vec<&str> = relative.split(separator).collect();
+  let mut guesses = vec![(None, components[0])];
+  if let (Platform::Windows, [organization, application, ..]) = (platform, components.as_slice()) {
+    guesses.insert(0, (Some(*organization), *application));
+  }
+
+  for (organization, application) in guesses {
+    let options = ProjectOptions {
+      qualifier: None,
+      organization: organization.map(String::from),
+      application: application.to_string(),
+      scope: Scope::User,
+    };
+    let mut found: Vec<Project> = Vec::new();
+    let mut shortest: Option<String> = None;
+    for project in Project::iter().filter(|project| *project != Project::PathFragment) {
+      let Ok(directory) = project.resolve(resolver, &options) else {
+        continue;
+      };
+      let Some(relative) = platform.strip_prefix(path, &directory) else {
+        continue;
+      };
+      match &shortest {
+        Some(shortest) if shortest.len() < relative.len() => (),
+        Some(shortest) if shortest.len() == relative.len() => found.push(project),
+        _ => {
+          found = vec![project];
+          shortest = Some(relative);
+        }
+      }
+    }
+    if let Some(relative) = shortest {
+      let fragment = options.fragment(platform).join(&separator.to_string());
+      return Some((fragment, found, relative));
+    }
+  }
+
+  let relative = components[1..].join(&separator.to_string());
+  Some((components[0].to_string(), projects, relative))
+}
+
+#[derive(Debug)]
+#[cfg_attr(feature = "serde1", derive(serde::Serialize, serde::Deserialize))]
+pub(crate) struct StructuredClassification {
+  pub(crate) path: String,
+  #[cfg_attr(
+    feature = "serde1",
+    serde(default, skip_serializing_if = "Vec::is_empty")
+  )]
+  pub(crate) directories: Vec<String>,
+  #[cfg_attr(
+    feature = "serde1",
+    serde(default, skip_serializing_if = "Option::is_none")
+  )]
+  pub(crate) project: Option<String>,
+  #[cfg_attr(
+    feature = "serde1",
+    serde(default, skip_serializing_if = "Vec::is_empty")
+  )]
+  pub(crate) project_directories: Vec<String>,
+  #[cfg_attr(
+    feature = "serde1",
+    serde(default, skip_serializing_if = "Option::is_none")
+  )]
+  pub(crate) relative: Option<String>,
+}
+
+impl StructuredClassification {
+  pub(crate) fn new(path: String, classification: Classification) -> Self {
+    let Classification {
+      base,
+      user,
+      project,
+      project_paths,
+      relative,
+    } = classification;
+    let directories = base
+      .iter()
+      .map(|path| format!("base {path}"))
+      .chain(user.iter().map(|path| format!("user {path}")))
+      .collect();
+    Self {
+      path,
+      directories,
+      project,
+      project_directories: project_paths
+        .iter()
+        .map(|path| format!("project {path}"))
+        .collect(),
+      relative,
+    }
+  }
+
+  pub(crate) fn render(&self, format: &Format) -> Result<String> {
+    format.render(self, || {
+      if self.directories.is_empty() {
+        return Ok(format!("{}: not inside a standard directory", self.path));
+      }
+      let mut parts = vec![format!("inside {}", self.directories.join(" or "))];
+      if let Some(project) = &self.project {
+        let directories = self.project_directories.join(" or ");
+        parts.push(format!(
+          "project {project} (path fragment of {directories})"
+        ));
+      }
+      if let Some(relative) = self.relative.as_deref().filter(|path| !path.is_empty()) {
+        parts.push(format!("relative {relative}"));
+      }
+      Ok(format!("{}: {}", self.path, parts.join(", ")))
+    })
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::platform::Environment;
+
+  fn linux() -> Resolver {
+    Resolver::new(Platform::Linux, Environment::new("/home/alice"))
+  }
+
+  fn render(path: &str) -> String {
+    let classification = whatis(&linux(), Path::new(path));
+    StructuredClassification::new(path.to_string(), classification)
+      .render(&Format::Text)
+      .unwrap()
+  }
+
+  #[test]
+  fn normalizes_lexically() {
+    let cases = [
+      (
+        Platform::Linux,
+        "/home/alice/./.cache//nifty/",
+        "/home/alice/.cache/nifty",
+      ),
+      (
+        Platform::Linux,
+        "/home/alice/.cache/../.config",
+        "/home/alice/.config",
+      ),
+      (Platform::Linux, "/../etc/../../tmp", "/tmp"),
+      (Platform::Linux, "a/../../b", "../b"),
+      (Platform::Linux, "//home/alice", "/home/alice"),
+      (Platform::Windows, r"C:\Users\alice\..\bob", r"C:\Users\bob"),
+      (Platform::Windows, r"C:\..\Windows", r"C:\Windows"),
+      (
+        Platform::Windows,
+        "C:/Users/alice/./AppData",
+        r"C:\Users\alice\AppData",
+      ),
+    ];
+    for (platform, path, normalized) in cases {
+      assert_eq!(
+        platform.normalize(Path::new(path)),
+        PathBuf::from(normalized),
+        "{path}"
+      );
+    }
+  }
+
+  #[test]
+  fn classifies_a_path_inside_a_base_directory() {
+    assert_eq!(
+      render("/home/alice/.cache"),
+      "/home/alice/.cache: inside base cache"
+    );
+  }
+
+  #[test]
+  fn classifies_a_path_inside_a_project_directory() {
+    assert_eq!(
+      render("/home/alice/.config/nifty/settings.toml"),
+      "/home/alice/.config/nifty/settings.toml: inside base config or base preference, \
+       project nifty (path fragment of project config or project preference), \
+       relative settings.toml"
+    );
+  }
+
+  #[test]
+  fn classifies_a_path_outside_every_directory() {
+    assert_eq!(
+      render("/srv/nifty/data"),
+      "/srv/nifty/data: not inside a standard directory"
+    );
+  }
+
+  #[test]
+  fn classifies_a_path_that_escapes_with_parent_components() {
+    assert_eq!(
+      render("/home/alice/.cache/../../bob/.cache/x"),
+      "/home/alice/.cache/../../bob/.cache/x: not inside a standard directory"
+    );
+    assert_eq!(
+      render("/home/alice/.cache/nifty/../../.config/nifty/x"),
+      "/home/alice/.cache/nifty/../../.config/nifty/x: inside base config or base preference, \
+       project nifty (path fragment of project config or project preference), relative x"
+    );
+  }
+}